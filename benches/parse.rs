@@ -0,0 +1,138 @@
+// benches/parse.rs
+
+//! Parsing benchmarks for the pages a full crawl fetches most, built from the
+//! test fixtures and scaled up to semester-sized pages.
+//!
+//! Run with `cargo bench --features bench --bench parse`.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use reqwest::Url;
+use spot_scraper::parsers::{course_detail, topic_detail};
+use spot_scraper::{Course, TaskStatus, WIB};
+use std::hint::black_box;
+
+const TOPIC: &str = include_str!("../tests/fixtures/topic.html");
+const ANSWER: &str = include_str!("../tests/fixtures/topic_answer.html");
+const GRADE: &str = include_str!("../tests/fixtures/topic_grade.html");
+
+/// Returns the part of `html` from the first `start` up to and including the next `end`.
+fn section<'a>(html: &'a str, start: &str, end: &str) -> &'a str {
+    let from = html.find(start).expect("fixture section start");
+    let to = from + html[from..].find(end).expect("fixture section end") + end.len();
+    &html[from..to]
+}
+
+/// The topic fixture with `tasks` graded tasks and as many materials.
+fn large_topic(tasks: usize) -> String {
+    let materials = section(TOPIC, "<div id=\"materi\">", "<div id=\"tugas\">");
+    let task = section(TOPIC, "<table class=\"table table-striped\">", "</table>");
+    let modal = section(TOPIC, "<div class=\"modal fade\"", "</form>");
+    let answer = ANSWER.replace("<!-- GRADE -->", GRADE);
+
+    let (head, _) = TOPIC.split_at(TOPIC.find(materials).unwrap());
+    let mut page = head.to_string();
+    page.push_str(&materials.replace("<div id=\"tugas\">", "").repeat(tasks));
+    page.push_str("<div id=\"tugas\">");
+    for _ in 0..tasks {
+        page.push_str(task);
+        page.push_str(&answer);
+        page.push_str(modal);
+        page.push_str("</div></div></div>");
+    }
+    page.push_str("</div></body></html>");
+    page
+}
+
+/// A course page listing `topics` topics, every third one still locked.
+fn large_course(topics: usize) -> String {
+    let mut page = String::from(
+        r#"<html><body><div class="container-fluid">
+<div class="white-box"><p>Deskripsi</p><p><a class="btn btn-danger" href="/mhs/rps/2510009533">RPS</a></p></div>
+<div class="row">"#,
+    );
+    for topic in 0..topics {
+        let link = if topic % 3 == 2 {
+            String::new()
+        } else {
+            format!(
+                r#"<a class="btn btn-info" href="/mhs/topik/2510009533/{}">Masuk</a>"#,
+                1370508 + topic
+            )
+        };
+        page.push_str(&format!(
+            r#"<div class="block4"><div class="panel panel-default">
+<div class="panel-heading">Topik {}</div>
+<div class="panel-body"><p>Materi</p>
+<div><div><button class="btn btn-default disabled">Waktu Akses: 04-09-2025 07:00</button></div></div>
+{}</div></div></div>"#,
+            topic + 1,
+            link
+        ));
+    }
+    page.push_str("</div></div></body></html>");
+    page
+}
+
+fn course() -> Course {
+    Course {
+        id: 2510009533,
+        code: "KU300".to_string(),
+        name: "PROYEK KONSULTANSI".to_string(),
+        credits: 2,
+        lecturer: "Dosen".to_string(),
+        academic_year: "2025/2026 - Ganjil".to_string(),
+        href: "/mhs/dashboard/2510009533".to_string(),
+    }
+}
+
+fn topic_pages(c: &mut Criterion) {
+    let base_url = Url::parse("https://spot.upi.edu").unwrap();
+    let mut group = c.benchmark_group("topic_detail");
+    for tasks in [1, 16, 128] {
+        let html = large_topic(tasks);
+        let parsed =
+            topic_detail::parse_topic_detail_from_html(&html, 1370508, 2510009533, &base_url, WIB)
+                .unwrap();
+        assert_eq!(parsed.tasks.len(), tasks);
+        assert!(
+            parsed
+                .tasks
+                .iter()
+                .all(|task| task.status() == TaskStatus::Graded)
+        );
+        group.throughput(Throughput::Bytes(html.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(tasks), &html, |b, html| {
+            b.iter(|| {
+                topic_detail::parse_topic_detail_from_html(
+                    black_box(html),
+                    1370508,
+                    2510009533,
+                    &base_url,
+                    WIB,
+                )
+                .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn course_pages(c: &mut Criterion) {
+    let mut group = c.benchmark_group("course_detail");
+    for topics in [3, 48] {
+        let html = large_course(topics);
+        let parsed = course_detail::parse_course_detail_from_html(&html, course(), WIB).unwrap();
+        assert_eq!(parsed.topics.len(), topics);
+        group.throughput(Throughput::Bytes(html.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(topics), &html, |b, html| {
+            b.iter(|| {
+                course_detail::parse_course_detail_from_html(black_box(html), course(), WIB)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, topic_pages, course_pages);
+criterion_main!(benches);
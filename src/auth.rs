@@ -0,0 +1,52 @@
+// src/auth.rs
+
+use crate::error::Result;
+
+/// A student ID (NIM) and password for the UPI CAS login.
+#[derive(Clone)]
+pub struct Credentials {
+    pub nim: String,
+    pub password: String,
+}
+
+impl Credentials {
+    pub fn new(nim: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            nim: nim.into(),
+            password: password.into(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("nim", &self.nim)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Supplies credentials when [`SpotClient`](crate::SpotClient) has to log in
+/// again after its session expired.
+///
+/// Implement this to read credentials lazily, e.g. from a keyring, instead of
+/// keeping the password in memory for the lifetime of the client.
+pub trait CredentialProvider: Send + Sync {
+    fn credentials(&self) -> Result<Credentials>;
+}
+
+impl CredentialProvider for Credentials {
+    fn credentials(&self) -> Result<Credentials> {
+        Ok(self.clone())
+    }
+}
+
+impl<F> CredentialProvider for F
+where
+    F: Fn() -> Result<Credentials> + Send + Sync,
+{
+    fn credentials(&self) -> Result<Credentials> {
+        self()
+    }
+}
@@ -0,0 +1,443 @@
+// src/bin/spot.rs
+
+//! `spot`: a command-line client for everyday SPOT tasks.
+//!
+//! Credentials are read from `SPOT_NIM` and `SPOT_PASSWORD` (a `.env` file
+//! works too). The session is kept in a file between runs, so only `login`
+//! or an expired session needs the password.
+
+use chrono::{DateTime, FixedOffset};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use spot_scraper::{
+    AnswerFile, AnswerSubmission, CalendarExporter, Course, Credentials, SpotClient, Task,
+    TaskFilter, TopicInfo, Watcher, WebhookFormat,
+};
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+type CliResult<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(name = "spot", version, about = "Command-line client for SPOT UPI")]
+struct Cli {
+    /// File where the login session is stored between runs.
+    #[arg(
+        long,
+        env = "SPOT_SESSION",
+        default_value = "spot-session.json",
+        global = true
+    )]
+    session: PathBuf,
+
+    /// Print JSON instead of a table.
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Save pages SPOT or CAS answered with when a login or parser fails.
+    #[arg(long, env = "SPOT_DEBUG_DIR", global = true)]
+    debug_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Log in with SPOT_NIM and SPOT_PASSWORD and save the session.
+    Login,
+    /// Show the logged-in student.
+    Whoami,
+    /// List enrolled courses.
+    Courses,
+    /// Show a course and its topics.
+    Course { course_id: u64 },
+    /// Show a topic's materials and tasks.
+    Topic { course_id: u64, topic_id: u64 },
+    /// List tasks across all courses.
+    Tasks {
+        /// Only show tasks that still need an answer.
+        #[arg(long)]
+        pending: bool,
+        /// With --pending, also show tasks whose deadline has passed.
+        #[arg(long, requires = "pending")]
+        overdue: bool,
+    },
+    /// Download an attachment by its href.
+    Download {
+        href: String,
+        /// Output file or directory.
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
+    },
+    /// Submit an answer for a task.
+    Submit {
+        course_id: u64,
+        topic_id: u64,
+        task_id: u64,
+        /// Answer text.
+        #[arg(long, default_value = "")]
+        text: String,
+        /// File to upload with the answer.
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Delete the existing answer first.
+        #[arg(long)]
+        replace: bool,
+    },
+    /// Export task deadlines as an iCalendar (.ics) file.
+    Calendar {
+        /// Output file. Prints to stdout when omitted.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Minutes before a deadline to show a reminder; repeat for several.
+        #[arg(long = "remind")]
+        reminders: Vec<i64>,
+        /// Also add events for when topics open.
+        #[arg(long)]
+        topics: bool,
+    },
+    /// Poll SPOT and post changes to webhooks until interrupted.
+    Watch {
+        /// Webhook URL to notify; repeat for several.
+        #[arg(long = "webhook", required = true)]
+        webhooks: Vec<String>,
+        /// Message layout. `auto` picks Discord or Slack from the webhook URL.
+        #[arg(long, value_enum, default_value_t = Format::Auto)]
+        format: Format,
+        /// Seconds between polls.
+        #[arg(long, default_value_t = 900)]
+        interval: u64,
+        /// Up to this many seconds are added to each interval at random.
+        #[arg(long, default_value_t = 60)]
+        jitter: u64,
+        /// File that collects notifications that could not be delivered.
+        #[arg(long, default_value = "spot-dead-letter.jsonl")]
+        dead_letter: PathBuf,
+        /// File that keeps the last snapshot between runs.
+        #[arg(long, default_value = "spot-watch-state.json")]
+        state: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Auto,
+    Json,
+    Slack,
+    Discord,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    dotenvy::dotenv().ok();
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> CliResult<()> {
+    let credentials = credentials_from_env();
+    let client = build_client(&cli, credentials.clone())?;
+
+    match cli.command {
+        Command::Login => {
+            let credentials =
+                credentials.ok_or("SPOT_NIM and SPOT_PASSWORD must be set to log in")?;
+            client
+                .login(&credentials.nim, &credentials.password)
+                .await?;
+            eprintln!("Logged in as {}.", credentials.nim);
+        }
+        Command::Whoami => {
+            let user = client.get_user_profile().await?;
+            output(cli.json, &user, || {
+                table(
+                    &["NIM", "NAME"],
+                    vec![vec![user.nim.clone(), user.name.clone()]],
+                )
+            })?;
+        }
+        Command::Courses => {
+            let courses = client.get_courses().await?;
+            output(cli.json, &courses, || {
+                let rows = courses
+                    .iter()
+                    .map(|c| {
+                        vec![
+                            c.id.to_string(),
+                            c.code.clone(),
+                            c.name.clone(),
+                            c.credits.to_string(),
+                            c.lecturer.clone(),
+                        ]
+                    })
+                    .collect();
+                table(&["ID", "CODE", "NAME", "SKS", "LECTURER"], rows)
+            })?;
+        }
+        Command::Course { course_id } => {
+            let course = find_course(&client, course_id).await?;
+            let detail = client.get_course_detail(&course).await?;
+            output(cli.json, &detail, || {
+                let rows = detail
+                    .topics
+                    .iter()
+                    .enumerate()
+                    .map(|(i, t)| {
+                        vec![
+                            (i + 1).to_string(),
+                            t.id.map(|id| id.to_string()).unwrap_or_default(),
+                            format_date(t.access_time),
+                            if t.is_accessible { "yes" } else { "no" }.to_string(),
+                        ]
+                    })
+                    .collect();
+                format!(
+                    "{} ({})\n{}\n\n{}",
+                    detail.course_info.name,
+                    detail.course_info.code,
+                    detail.description,
+                    table(&["#", "TOPIC ID", "ACCESS TIME", "OPEN"], rows)
+                )
+            })?;
+        }
+        Command::Topic {
+            course_id,
+            topic_id,
+        } => {
+            let topic = client
+                .get_topic_detail(&topic_info(course_id, topic_id))
+                .await?;
+            output(cli.json, &topic, || {
+                let mut text = topic.description.clone().unwrap_or_default();
+                text.push_str(&format!("\n\n{} material(s)\n\n", topic.contents.len()));
+                text.push_str(&task_table(topic.tasks.iter().map(|t| ("", t))));
+                text
+            })?;
+        }
+        Command::Tasks { pending, overdue } => {
+            let tasks = if pending {
+                let mut filter = TaskFilter::pending();
+                if overdue {
+                    filter = filter.overdue();
+                }
+                client.get_tasks(&filter).await?
+            } else {
+                client.get_all_tasks().await?
+            };
+            output(cli.json, &tasks, || {
+                task_table(tasks.iter().map(|t| (t.course.code.as_str(), &t.task)))
+            })?;
+        }
+        Command::Download { href, output: path } => {
+            let (download, path) = client.download_to_path(&href, &path).await?;
+            eprintln!("Saved {} bytes to {}.", download.size, path.display());
+        }
+        Command::Submit {
+            course_id,
+            topic_id,
+            task_id,
+            text,
+            file,
+            replace,
+        } => {
+            let topic = client
+                .get_topic_detail(&topic_info(course_id, topic_id))
+                .await?;
+            let task = topic
+                .tasks
+                .iter()
+                .find(|t| t.id == Some(task_id))
+                .ok_or_else(|| format!("task {} not found in topic {}", task_id, topic_id))?;
+
+            let mut submission = AnswerSubmission::text(text);
+            if let Some(file) = file {
+                submission = submission.with_file(AnswerFile::from_path(file).await?);
+            }
+            let answer = if replace {
+                client.replace_answer(task, submission).await?
+            } else {
+                client.submit_task_answer(task, submission).await?
+            };
+            output(cli.json, &answer, || {
+                format!(
+                    "Submitted answer {} at {}.",
+                    answer.id.map(|id| id.to_string()).unwrap_or_default(),
+                    format_date(answer.date_submitted)
+                )
+            })?;
+        }
+        Command::Calendar {
+            output,
+            reminders,
+            topics,
+        } => {
+            let snapshot = client.snapshot().await?;
+            let mut exporter = CalendarExporter::new().topic_access_times(topics);
+            for minutes in reminders {
+                exporter = exporter.reminder(chrono::Duration::minutes(minutes));
+            }
+            let ics = exporter.export(&snapshot);
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, ics)?;
+                    eprintln!("Saved calendar to {}.", path.display());
+                }
+                None => print!("{}", ics),
+            }
+        }
+        Command::Watch {
+            webhooks,
+            format,
+            interval,
+            jitter,
+            dead_letter,
+            state,
+        } => {
+            let mut builder = Watcher::builder(client)
+                .interval(Duration::from_secs(interval))
+                .jitter(Duration::from_secs(jitter))
+                .dead_letter_file(dead_letter)
+                .state_file(state);
+            for url in &webhooks {
+                builder = builder.webhook(url, webhook_format(format, url))?;
+            }
+            let mut watcher = builder.build()?;
+
+            eprintln!(
+                "Watching SPOT every {} seconds. Press Ctrl-C to stop.",
+                interval
+            );
+            let shutdown = async {
+                tokio::signal::ctrl_c().await.ok();
+            };
+            watcher
+                .run_until(shutdown, |e| eprintln!("poll failed: {}", e))
+                .await;
+            watcher.client().save_session(&cli.session).await?;
+            return Ok(());
+        }
+    }
+
+    client.save_session(&cli.session).await?;
+    Ok(())
+}
+
+fn credentials_from_env() -> Option<Credentials> {
+    let nim = std::env::var("SPOT_NIM").ok()?;
+    let password = std::env::var("SPOT_PASSWORD").ok()?;
+    Some(Credentials::new(nim, password))
+}
+
+/// Restores the saved session and, when credentials are available, lets the
+/// client log in again by itself once that session expires.
+fn build_client(cli: &Cli, credentials: Option<Credentials>) -> CliResult<SpotClient> {
+    let mut builder = SpotClient::builder();
+    if cli.session.exists() {
+        builder = builder.session_file(&cli.session)?;
+    }
+    if let Some(dir) = &cli.debug_dir {
+        builder = builder.debug_dump_dir(dir);
+    }
+    if let Some(credentials) = credentials {
+        builder = builder.credentials(credentials);
+    }
+    Ok(builder.build()?)
+}
+
+fn webhook_format(format: Format, url: &str) -> WebhookFormat {
+    match format {
+        Format::Json => WebhookFormat::Json,
+        Format::Slack => WebhookFormat::Slack,
+        Format::Discord => WebhookFormat::Discord,
+        Format::Auto if url.contains("hooks.slack.com") => WebhookFormat::Slack,
+        Format::Auto if url.contains("discord.com/api/webhooks") => WebhookFormat::Discord,
+        Format::Auto => WebhookFormat::Json,
+    }
+}
+
+async fn find_course(client: &SpotClient, course_id: u64) -> CliResult<Course> {
+    client
+        .get_courses()
+        .await?
+        .into_iter()
+        .find(|c| c.id == course_id)
+        .ok_or_else(|| format!("course {} not found", course_id).into())
+}
+
+fn topic_info(course_id: u64, topic_id: u64) -> TopicInfo {
+    TopicInfo {
+        id: Some(topic_id),
+        course_id: Some(course_id),
+        access_time: None,
+        is_accessible: true,
+        href: Some(format!("/mhs/topik/{}/{}", course_id, topic_id)),
+    }
+}
+
+fn task_table<'a>(tasks: impl Iterator<Item = (&'a str, &'a Task)>) -> String {
+    let rows = tasks
+        .map(|(course, t)| {
+            vec![
+                course.to_string(),
+                t.topic_id.to_string(),
+                t.id.map(|id| id.to_string()).unwrap_or_default(),
+                t.title.clone(),
+                format_date(t.due_date),
+                format!("{:?}", t.status()),
+            ]
+        })
+        .collect();
+    table(&["COURSE", "TOPIC", "TASK", "TITLE", "DUE", "STATUS"], rows)
+}
+
+fn output<T: Serialize>(json: bool, value: &T, render: impl FnOnce() -> String) -> CliResult<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    } else {
+        println!("{}", render());
+    }
+    Ok(())
+}
+
+/// Formats a date in the zone SPOT wrote it in, as shown on the website.
+fn format_date(date: Option<DateTime<FixedOffset>>) -> String {
+    date.map(|d| d.format("%d-%m-%Y %H:%M").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Renders rows as a plain-text table with left-aligned, padded columns.
+fn table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let render_row = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![render_row(headers.to_vec())];
+    lines.extend(
+        rows.iter()
+            .map(|row| render_row(row.iter().map(String::as_str).collect())),
+    );
+    lines.join("\n")
+}
@@ -1,135 +1,999 @@
-use crate::error::{Result, ScraperError};
-use crate::models::{Course, DetailCourse, TopicDetail, TopicInfo, User};
+use crate::auth::CredentialProvider;
+use crate::dates::{self, WIB};
+use crate::debug::{DirectoryDump, FailedPage, PageDump};
+use crate::error::{LoginFailure, PageKind, ParseProblem, Result, ScraperError};
+use crate::files::{self, TempFile};
+use crate::models::{
+    Answer, AnswerSubmission, Course, CourseSnapshot, CourseTask, DetailCourse, Download, Rps,
+    RpsDetail, Snapshot, Task, TopicDetail, TopicInfo, User,
+};
 use crate::parsers;
-use reqwest::cookie::Jar;
-use reqwest::header::{HeaderMap, USER_AGENT};
-use scraper::{Html, Selector};
+use crate::parsers::selectors;
+use crate::retry::{self, RetryPolicy};
+use crate::session;
+use crate::tasks::TaskFilter;
+use crate::throttle::{RateLimit, Throttle, Throttled};
+use chrono::{FixedOffset, Utc};
+use percent_encoding::percent_decode_str;
+use reqwest::header::{CONTENT_DISPOSITION, CONTENT_TYPE, HeaderMap, HeaderValue, USER_AGENT};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Proxy, Request, RequestBuilder, Response, StatusCode, Url};
+use reqwest_cookie_store::{CookieStore, CookieStoreMutex};
+use scraper::Html;
 use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
 
-const SSO_LOGIN_PAGE_URL: &str =
-    "https://sso.upi.edu/cas/login?service=https://spot.upi.edu/beranda";
+const DEFAULT_BASE_URL: &str = "https://spot.upi.edu";
+const DEFAULT_SSO_LOGIN_URL: &str = "https://sso.upi.edu/cas/login";
+const DEFAULT_SERVICE_PATH: &str = "/beranda";
+const DEFAULT_MAX_REAUTH_ATTEMPTS: u32 = 3;
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/100.0.0.0 Safari/537.36";
+
+/// Configures and creates a [`SpotClient`].
+///
+/// Every setting has a default that points at the production SPOT and UPI CAS
+/// servers, so `SpotClientBuilder::new().build()` behaves like [`SpotClient::new`].
+#[derive(Default)]
+pub struct SpotClientBuilder {
+    base_url: Option<String>,
+    sso_login_url: Option<String>,
+    service_url: Option<String>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<Proxy>,
+    user_agent: Option<String>,
+    default_headers: HeaderMap,
+    session: Option<String>,
+    credentials: Option<Arc<dyn CredentialProvider>>,
+    max_reauth_attempts: Option<u32>,
+    timezone: Option<FixedOffset>,
+    rate_limit: Option<RateLimit>,
+    host_rate_limits: HashMap<String, RateLimit>,
+    max_concurrency: Option<usize>,
+    retry: Option<RetryPolicy>,
+    page_dump: Option<Arc<dyn PageDump>>,
+}
+
+impl SpotClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the SPOT base URL, e.g. `https://spot.upi.edu` or a local stand-in server.
+    pub fn base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = Some(url.into());
+        self
+    }
+
+    /// Sets the CAS login page URL, without the `service` query parameter.
+    pub fn sso_login_url(mut self, url: impl Into<String>) -> Self {
+        self.sso_login_url = Some(url.into());
+        self
+    }
+
+    /// Sets the `service` parameter sent to CAS. Defaults to `<base_url>/beranda`.
+    pub fn service_url(mut self, url: impl Into<String>) -> Self {
+        self.service_url = Some(url.into());
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for a whole request, from connecting until the body is read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Adds headers sent with every request. Later calls override earlier values.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        for (name, value) in headers.iter() {
+            self.default_headers.insert(name.clone(), value.clone());
+        }
+        self
+    }
+
+    /// Restores a session previously saved with [`SpotClient::export_session`].
+    pub fn session(mut self, session: impl Into<String>) -> Self {
+        self.session = Some(session.into());
+        self
+    }
+
+    /// Restores a session previously saved with [`SpotClient::save_session`].
+    pub fn session_file(self, path: impl AsRef<Path>) -> Result<Self> {
+        let session = std::fs::read_to_string(path)?;
+        Ok(self.session(session))
+    }
+
+    /// Enables automatic re-authentication.
+    ///
+    /// When a GET request finds the session expired, the client logs in again
+    /// with credentials from `provider` and retries the request once.
+    pub fn credentials(mut self, provider: impl CredentialProvider + 'static) -> Self {
+        self.credentials = Some(Arc::new(provider));
+        self
+    }
+
+    /// Limits how many times in a row the client re-authenticates without a
+    /// request succeeding in between. Defaults to 3.
+    pub fn max_reauth_attempts(mut self, attempts: u32) -> Self {
+        self.max_reauth_attempts = Some(attempts);
+        self
+    }
+
+    /// Sets the zone SPOT's dates are written in. Defaults to [`WIB`](crate::WIB).
+    pub fn timezone(mut self, zone: FixedOffset) -> Self {
+        self.timezone = Some(zone);
+        self
+    }
+
+    /// Sets the rate limit for every host without its own limit. Defaults to
+    /// [`RateLimit::default`]; use [`RateLimit::unlimited`] to turn it off.
+    ///
+    /// Each host has its own bucket, so logging in through CAS does not use
+    /// up the budget for SPOT pages. Redirects that a request follows, such as
+    /// the hop from CAS back to SPOT, are not counted against any bucket.
+    pub fn rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = Some(limit);
+        self
+    }
+
+    /// Sets the rate limit for requests to `host`, e.g. `sso.upi.edu`.
+    pub fn host_rate_limit(mut self, host: impl Into<String>, limit: RateLimit) -> Self {
+        self.host_rate_limits.insert(host.into(), limit);
+        self
+    }
+
+    /// Limits how many requests may be in flight at once, across all hosts,
+    /// from sending until their body has been read. Defaults to 4.
+    pub fn max_concurrency(mut self, requests: usize) -> Self {
+        self.max_concurrency = Some(requests);
+        self
+    }
+
+    /// Sets how page and file downloads are retried after transient failures.
+    /// Defaults to [`RetryPolicy::default`]. Form submissions are never retried.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Passes every page that made a login or a parser fail to `dump`. Off by
+    /// default.
+    pub fn debug_dump(mut self, dump: impl PageDump + 'static) -> Self {
+        self.page_dump = Some(Arc::new(dump));
+        self
+    }
+
+    /// Writes every page that made a login or a parser fail to a file in
+    /// `dir`. See [`DirectoryDump`].
+    pub fn debug_dump_dir(self, dir: impl Into<PathBuf>) -> Self {
+        self.debug_dump(DirectoryDump::new(dir))
+    }
+
+    pub fn build(self) -> Result<SpotClient> {
+        selectors::validate()?;
+
+        let base_url = parse_url(self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL))?;
+        let base_url = base_url.as_str().trim_end_matches('/').to_string();
+
+        let service_url = match self.service_url {
+            Some(url) => parse_url(&url)?.to_string(),
+            None => format!("{}{}", base_url, DEFAULT_SERVICE_PATH),
+        };
+        let sso_login_url = Url::parse_with_params(
+            parse_url(
+                self.sso_login_url
+                    .as_deref()
+                    .unwrap_or(DEFAULT_SSO_LOGIN_URL),
+            )?
+            .as_str(),
+            &[("service", service_url.as_str())],
+        )
+        .map_err(|e| ScraperError::InvalidConfig(format!("invalid SSO login URL: {}", e)))?;
+
+        let mut headers = self.default_headers;
+        let user_agent = self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT);
+        let user_agent = HeaderValue::from_str(user_agent)
+            .map_err(|e| ScraperError::InvalidConfig(format!("invalid user agent: {}", e)))?;
+        headers.insert(USER_AGENT, user_agent);
+
+        let cookie_store = match self.session.as_deref() {
+            Some(session) => session::import_cookie_store(session)?,
+            None => CookieStore::default(),
+        };
+        let cookie_store = Arc::new(CookieStoreMutex::new(cookie_store));
+        let mut builder = reqwest::Client::builder()
+            .cookie_provider(Arc::clone(&cookie_store))
+            .default_headers(headers);
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = self.proxy {
+            builder = builder.proxy(proxy);
+        }
+
+        let max_concurrency = self
+            .max_concurrency
+            .unwrap_or(DEFAULT_MAX_CONCURRENCY)
+            .max(1);
+        Ok(SpotClient {
+            client: builder.build()?,
+            base_url,
+            sso_login_url,
+            cookie_store,
+            credentials: self.credentials,
+            max_reauth_attempts: self
+                .max_reauth_attempts
+                .unwrap_or(DEFAULT_MAX_REAUTH_ATTEMPTS),
+            timezone: self.timezone.unwrap_or(WIB),
+            throttle: Throttle::new(
+                self.rate_limit.unwrap_or_default(),
+                self.host_rate_limits,
+                max_concurrency,
+            ),
+            max_concurrency,
+            retry: self.retry.unwrap_or_default(),
+            page_dump: self.page_dump,
+            reauth: Mutex::new(()),
+            login_generation: AtomicU64::new(0),
+            consecutive_reauths: AtomicU32::new(0),
+        })
+    }
+}
+
+fn parse_url(url: &str) -> Result<Url> {
+    Url::parse(url)
+        .map_err(|e| ScraperError::InvalidConfig(format!("invalid URL '{}': {}", url, e)))
+}
 
 pub struct SpotClient {
     client: reqwest::Client,
     base_url: String,
+    sso_login_url: Url,
+    cookie_store: Arc<CookieStoreMutex>,
+    credentials: Option<Arc<dyn CredentialProvider>>,
+    max_reauth_attempts: u32,
+    timezone: FixedOffset,
+    throttle: Throttle,
+    max_concurrency: usize,
+    retry: RetryPolicy,
+    page_dump: Option<Arc<dyn PageDump>>,
+    /// Serializes re-authentication so concurrent requests log in only once.
+    reauth: Mutex<()>,
+    /// Incremented after every successful login.
+    login_generation: AtomicU64,
+    consecutive_reauths: AtomicU32,
+}
+
+impl Default for SpotClient {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SpotClient {
+    /// Creates a client for the production SPOT server with default settings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the underlying HTTP client cannot be initialized. Use
+    /// [`SpotClient::builder`] to handle that case as an error instead.
     pub fn new() -> Self {
-        let cookie_jar = Arc::new(Jar::default());
-
-        // --- CHANGE 1: Add a default User-Agent header ---
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            USER_AGENT,
-            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/100.0.0.0 Safari/537.36"
-                .parse()
-                .unwrap(),
-        );
-
-        let client = reqwest::Client::builder()
-            .cookie_provider(cookie_jar)
-            .default_headers(headers) // Use the headers
+        SpotClientBuilder::new()
             .build()
-            .unwrap();
+            .expect("default SpotClient configuration should be valid")
+    }
+
+    pub fn builder() -> SpotClientBuilder {
+        SpotClientBuilder::new()
+    }
+
+    /// The zone SPOT's dates are read in.
+    pub fn timezone(&self) -> FixedOffset {
+        self.timezone
+    }
+
+    /// How many requests may wait for a response at once.
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    /// Creates a client for the production SPOT server that reuses a session
+    /// saved with [`SpotClient::save_session`].
+    pub fn from_session_file(path: impl AsRef<Path>) -> Result<Self> {
+        SpotClientBuilder::new().session_file(path)?.build()
+    }
 
-        Self {
-            client,
-            base_url: "https://spot.upi.edu".to_string(),
+    /// Serializes the session cookies so a later run can skip logging in.
+    ///
+    /// The result contains credentials equivalent to a password; store it
+    /// somewhere only the user can read.
+    pub fn export_session(&self) -> Result<String> {
+        session::export_cookie_store(&self.cookie_store)
+    }
+
+    /// Writes the session cookies to `path`. See [`SpotClient::export_session`].
+    ///
+    /// The file is as good as a password. On unix it is created readable by
+    /// its owner only (mode 0600), and it is replaced atomically, so an
+    /// interrupted save never leaves a truncated session behind.
+    pub async fn save_session(&self, path: impl AsRef<Path>) -> Result<()> {
+        let session = self.export_session()?;
+        files::write_private(path.as_ref(), session.as_bytes()).await?;
+        Ok(())
+    }
+
+    /// Checks whether the current session can open the student dashboard.
+    ///
+    /// A redirect away from `/mhs` is reported as `Ok(false)` rather than
+    /// [`ScraperError::SessionExpired`].
+    pub async fn is_logged_in(&self) -> Result<bool> {
+        match self.get_once("/mhs").await {
+            Ok(_) => Ok(true),
+            Err(ScraperError::SessionExpired) => Ok(false),
+            Err(e) => Err(e),
         }
     }
 
     /// Logs into SPOT using a student ID (NIM) and password.
     pub async fn login(&self, nim: &str, password: &str) -> Result<()> {
         // --- STEP 1: GET the login page to get the "execution" token ---
-        let response = self.client.get(SSO_LOGIN_PAGE_URL).send().await?;
+        let response = self
+            .send(self.client.get(self.sso_login_url.clone()))
+            .await?;
 
         // The service URL is now part of the request URL itself
         let login_action_url = response.url().clone();
+        let status = response.status();
 
         let response_text = response.text().await?;
-        let document = Html::parse_document(&response_text);
-
-        let token_selector = Selector::parse("input[name=\"execution\"]").unwrap();
-
-        let execution_token = document
-            .select(&token_selector)
+        // `Html` is not `Send`, so it must be gone before the next await.
+        let execution_token = Html::parse_document(&response_text)
+            .select(&selectors::get().cas_execution)
             .next()
             .and_then(|element| element.value().attr("value"))
-            .ok_or(ScraperError::TokenNotFound)?;
+            .map(String::from);
+        let Some(execution_token) = execution_token else {
+            // Without a login form CAS is usually down or asking for a captcha.
+            let error = match self.diagnose_login(&response_text, status, &login_action_url) {
+                error @ ScraperError::AuthenticationFailed {
+                    reason:
+                        LoginFailure::Maintenance
+                        | LoginFailure::CaptchaRequired
+                        | LoginFailure::UnexpectedRedirect,
+                    ..
+                } => error,
+                _ => ScraperError::TokenNotFound,
+            };
+            self.dump_page(
+                PageKind::Login,
+                login_action_url.as_str(),
+                &response_text,
+                &error,
+            );
+            return Err(error);
+        };
 
         // --- STEP 2: POST credentials to the correct URL with all fields ---
         let mut params = HashMap::new();
         params.insert("username", nim);
         params.insert("password", password);
-        params.insert("execution", execution_token);
+        params.insert("execution", execution_token.as_str());
         params.insert("_eventId", "submit");
 
         // --- CHANGE 2: Post to the full URL including the '?service=...' part ---
         let response = self
-            .client
-            .post(login_action_url)
-            .form(&params)
-            .send()
+            .send(self.client.post(login_action_url).form(&params))
             .await?;
 
         // --- STEP 3: Verify the final redirection URL ---
         let final_url = response.url().clone();
-        if final_url.host_str() != Some("spot.upi.edu") {
+        if !self.is_spot_url(&final_url) {
+            let status = response.status();
             let error_body = response.text().await.unwrap_or_default();
-            std::fs::write("login_fail.html", error_body).ok();
-            println!(
-                "Login failed. Check login_fail.html for details. The final URL was: {}",
-                final_url
-            );
-
-            return Err(ScraperError::AuthenticationFailed);
+            let error = self.diagnose_login(&error_body, status, &final_url);
+            self.dump_page(PageKind::Login, final_url.as_str(), &error_body, &error);
+            return Err(error);
         }
 
+        self.login_generation.fetch_add(1, Ordering::SeqCst);
         Ok(())
     }
 
-    async fn get_html(&self, path: &str) -> Result<String> {
-        let url = format!("{}{}", self.base_url, path);
-        let response = self.client.get(&url).send().await?;
+    /// Turns the page a failed login ended on into an
+    /// [`AuthenticationFailed`](ScraperError::AuthenticationFailed) error.
+    fn diagnose_login(&self, html: &str, status: StatusCode, final_url: &Url) -> ScraperError {
+        let (reason, message) =
+            parsers::cas::diagnose_login_failure(html, status, final_url, &self.sso_login_url);
+        ScraperError::AuthenticationFailed {
+            reason,
+            message,
+            url: final_url.to_string(),
+        }
+    }
+
+    /// Hands a page that made a request fail to the debug dump, if one is set.
+    fn dump_page(&self, kind: PageKind, url: &str, html: &str, error: &ScraperError) {
+        if let Some(dump) = &self.page_dump {
+            dump.dump(&FailedPage {
+                kind,
+                url,
+                html,
+                error,
+            });
+        }
+    }
+
+    /// Runs `parse` on a fetched page, adding the page address to a parsing
+    /// error and dumping the page if it fails.
+    fn parse_page<T>(
+        &self,
+        kind: PageKind,
+        href: &str,
+        html: &str,
+        parse: impl FnOnce(&str) -> Result<T>,
+    ) -> Result<T> {
+        let url = self.absolute_url(href);
+        parse(html).map_err(|error| {
+            let error = error.with_url(&url);
+            self.dump_page(kind, &url, html, &error);
+            error
+        })
+    }
+
+    /// Returns true if `url` points at the configured SPOT server.
+    fn is_spot_url(&self, url: &Url) -> bool {
+        Url::parse(&self.base_url)
+            .map(|base| base.origin() == url.origin())
+            .unwrap_or(false)
+    }
+
+    /// Turns an href scraped from a page into an absolute URL on the SPOT server.
+    fn absolute_url(&self, href: &str) -> String {
+        if href.starts_with("http://") || href.starts_with("https://") {
+            href.to_string()
+        } else {
+            format!("{}{}", self.base_url, href)
+        }
+    }
+
+    /// Sends a GET request for `href`, retrying transient failures as the
+    /// client's [`RetryPolicy`] allows.
+    async fn get(&self, href: &str) -> Result<Throttled<'_>> {
+        self.retrying(|| self.get_renewing_session(href)).await
+    }
+
+    /// Sends a GET request for `href`, logging in again and retrying once if
+    /// the session expired and a credential provider is configured.
+    async fn get_renewing_session(&self, href: &str) -> Result<Throttled<'_>> {
+        let generation = self.login_generation.load(Ordering::SeqCst);
+        match self.get_once(href).await {
+            Err(ScraperError::SessionExpired) if self.credentials.is_some() => {
+                self.reauthenticate(generation).await?;
+                let response = self.get_once(href).await?;
+                self.consecutive_reauths.store(0, Ordering::SeqCst);
+                Ok(response)
+            }
+            Ok(response) => {
+                self.consecutive_reauths.store(0, Ordering::SeqCst);
+                Ok(response)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Logs in again unless another request already did so since `generation`.
+    async fn reauthenticate(&self, generation: u64) -> Result<()> {
+        let Some(provider) = &self.credentials else {
+            return Err(ScraperError::SessionExpired);
+        };
+        let _guard = self.reauth.lock().await;
+        if self.login_generation.load(Ordering::SeqCst) != generation {
+            return Ok(());
+        }
+        if self.consecutive_reauths.fetch_add(1, Ordering::SeqCst) >= self.max_reauth_attempts {
+            return Err(ScraperError::SessionExpired);
+        }
+        let credentials = provider.credentials()?;
+        self.login(&credentials.nim, &credentials.password).await
+    }
+
+    /// Sends a single GET request for `href`.
+    ///
+    /// SPOT answers requests without a valid session by redirecting to another
+    /// page, so ending up outside the requested path means the session expired.
+    /// Any other non-success status is reported as [`ScraperError::HttpStatus`]
+    /// instead of being parsed as if it were the requested page.
+    async fn get_once(&self, href: &str) -> Result<Throttled<'_>> {
+        let request = self.client.get(self.absolute_url(href)).build()?;
+        let requested_url = request.url().clone();
+        let response = self.execute(request).await?;
 
-        if !response.url().path().starts_with(path) {
+        if self.is_spot_url(&requested_url)
+            && !response.url().path().starts_with(requested_url.path())
+        {
             return Err(ScraperError::SessionExpired);
         }
 
-        Ok(response.text().await?)
+        error_for_status(response)
+    }
+
+    /// Runs `request` until it succeeds, fails with an error that is not
+    /// [retryable](ScraperError::is_retryable), or runs out of attempts.
+    async fn retrying<T, F, Fut>(&self, mut request: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match request().await {
+                Err(e) if e.is_retryable() && attempt < self.retry.max_attempts() => {
+                    let retry_after = match &e {
+                        ScraperError::HttpStatus { retry_after, .. } => *retry_after,
+                        _ => None,
+                    };
+                    tokio::time::sleep(self.retry.delay(attempt, retry_after)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Throttled<'_>> {
+        self.execute(request.build()?).await
+    }
+
+    /// Sends `request` once the rate limit of its host and the concurrency
+    /// limit allow it. Every request to SPOT and CAS goes through here.
+    ///
+    /// The request slot stays taken until the returned response is read or
+    /// dropped.
+    async fn execute(&self, request: Request) -> Result<Throttled<'_>> {
+        let permit = self.throttle.acquire(request.url()).await;
+        let response = self.client.execute(request).await?;
+        Ok(Throttled::new(response, permit))
+    }
+
+    /// Fetches a page as text. The body is read inside the retry, so a
+    /// connection dropped halfway through a page is retried as well.
+    async fn get_html(&self, path: &str) -> Result<String> {
+        self.retrying(|| async { Ok(self.get_renewing_session(path).await?.text().await?) })
+            .await
+    }
+
+    /// Streams an attachment into `writer` using the logged-in session.
+    ///
+    /// `href` is any file link scraped from SPOT, such as [`Task::file`],
+    /// [`Answer::file_href`] or [`Rps::href`].
+    pub async fn download<W>(&self, href: &str, writer: &mut W) -> Result<Download>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut response = self.get(href).await?;
+        let mut download = download_info(&response);
+
+        while let Some(chunk) = response.chunk().await? {
+            writer.write_all(&chunk).await?;
+            download.size += chunk.len() as u64;
+        }
+        writer.flush().await?;
+
+        Ok(download)
+    }
+
+    /// Downloads an attachment to `path`.
+    ///
+    /// If `path` is an existing directory, the file is saved inside it under the
+    /// name from `Content-Disposition`, falling back to the last URL segment.
+    ///
+    /// The file only appears at its final path once it has been downloaded
+    /// completely; a failed download leaves an existing file there untouched.
+    pub async fn download_to_path(
+        &self,
+        href: &str,
+        path: impl AsRef<Path>,
+    ) -> Result<(Download, PathBuf)> {
+        let mut response = self.get(href).await?;
+        let mut download = download_info(&response);
+
+        let mut path = path.as_ref().to_path_buf();
+        if tokio::fs::metadata(&path).await.is_ok_and(|m| m.is_dir()) {
+            let file_name = download
+                .file_name
+                .clone()
+                .or_else(|| {
+                    response
+                        .url()
+                        .path_segments()?
+                        .next_back()
+                        .filter(|s| !s.is_empty())
+                        .map(String::from)
+                })
+                .unwrap_or_else(|| "download".to_string());
+            path.push(sanitize_file_name(&file_name));
+        }
+
+        // Written next to `path` and renamed on success, so a failed download
+        // never leaves a truncated file behind.
+        let mut file = TempFile::create(&path, false).await?;
+        let written = async {
+            while let Some(chunk) = response.chunk().await? {
+                file.write_all(&chunk).await?;
+                download.size += chunk.len() as u64;
+            }
+            Ok::<_, ScraperError>(())
+        }
+        .await;
+        if let Err(e) = written {
+            file.discard().await;
+            return Err(e);
+        }
+        file.persist().await?;
+
+        Ok((download, path))
     }
 
     pub async fn get_user_profile(&self) -> Result<User> {
         let html_content = self.get_html("/mhs").await?;
-        parsers::user::parse_user_from_html(&html_content)
+        self.parse_page(
+            PageKind::Dashboard,
+            "/mhs",
+            &html_content,
+            parsers::user::parse_user_from_html,
+        )
     }
 
     pub async fn get_courses(&self) -> Result<Vec<Course>> {
         let html_content = self.get_html("/mhs").await?;
-        parsers::courses::parse_courses_from_html(&html_content)
+        self.parse_page(
+            PageKind::Dashboard,
+            "/mhs",
+            &html_content,
+            parsers::courses::parse_courses_from_html,
+        )
     }
 
     pub async fn get_course_detail(&self, course: &Course) -> Result<DetailCourse> {
         // The href for the detail page is already stored in the Course struct
         let html_content: String = self.get_html(&course.href).await?;
-        parsers::course_detail::parse_course_detail_from_html(&html_content, course.clone())
+        self.parse_page(
+            PageKind::CourseDetail,
+            &course.href,
+            &html_content,
+            |html| {
+                parsers::course_detail::parse_course_detail_from_html(
+                    html,
+                    course.clone(),
+                    self.timezone,
+                )
+            },
+        )
+    }
+
+    /// Fetches and parses a course's RPS: learning outcomes, weekly plan,
+    /// assessment weights and references.
+    pub async fn get_rps(&self, rps: &Rps) -> Result<RpsDetail> {
+        let href = rps
+            .href
+            .as_ref()
+            .ok_or_else(|| missing(PageKind::Rps, ParseProblem::MissingHref))?;
+        let html_content = self.get_html(href).await?;
+        self.parse_page(PageKind::Rps, href, &html_content, |html| {
+            parsers::rps::parse_rps_from_html(html, rps.clone())
+        })
     }
 
     pub async fn get_topic_detail(&self, topic_info: &TopicInfo) -> Result<TopicDetail> {
-        let href = topic_info.href.as_ref().ok_or_else(|| {
-            ScraperError::ParsingError("TopicInfo tidak memiliki href yang valid".to_string())
-        })?;
+        let missing = |problem| missing(PageKind::TopicDetail, problem);
+        let href = topic_info
+            .href
+            .as_ref()
+            .ok_or_else(|| missing(ParseProblem::MissingHref))?;
+        let course_id = topic_info
+            .course_id
+            .ok_or_else(|| missing(ParseProblem::MissingCourseId))?;
+        let topic_id = topic_info
+            .id
+            .ok_or_else(|| missing(ParseProblem::MissingTopicId))?;
+
+        let html_content = self.get_html(href).await?;
+        let page_url = Url::parse(&self.absolute_url(href))
+            .map_err(|e| ScraperError::InvalidConfig(format!("invalid topic URL: {}", e)))?;
+        self.parse_page(PageKind::TopicDetail, href, &html_content, |html| {
+            parsers::topic_detail::parse_topic_detail_from_html(
+                html,
+                topic_id,
+                course_id,
+                &page_url,
+                self.timezone,
+            )
+        })
+    }
+
+    /// Walks every course and accessible topic and collects all tasks, sorted
+    /// by due date.
+    pub async fn get_all_tasks(&self) -> Result<Vec<CourseTask>> {
+        self.get_tasks(&TaskFilter::new().sort_by_due_date()).await
+    }
+
+    /// Like [`SpotClient::get_all_tasks`], but keeps and orders tasks as `filter` says.
+    pub async fn get_tasks(&self, filter: &TaskFilter) -> Result<Vec<CourseTask>> {
+        let mut tasks = Vec::new();
+        for course in self.get_courses().await? {
+            let detail = self.get_course_detail(&course).await?;
+            for topic in detail.topics.into_iter().filter(|t| t.is_accessible) {
+                let topic_detail = self.get_topic_detail(&topic).await?;
+                tasks.extend(topic_detail.tasks.into_iter().map(|task| CourseTask {
+                    course: course.clone(),
+                    topic: topic.clone(),
+                    task,
+                }));
+            }
+        }
+        Ok(filter.apply(tasks))
+    }
 
-        let course_id = topic_info.course_id.ok_or_else(|| {
-            ScraperError::ParsingError("TopicInfo tidak memiliki course_id yang valid".to_string())
+    /// Fetches every course with the details of its accessible topics, for
+    /// comparing with an earlier snapshot through [`diff`](crate::diff()).
+    pub async fn snapshot(&self) -> Result<Snapshot> {
+        let taken_at = dates::now(self.timezone);
+        let mut courses = Vec::new();
+        for course in self.get_courses().await? {
+            let detail = self.get_course_detail(&course).await?;
+            let mut topics = Vec::new();
+            for topic in detail.topics.iter().filter(|t| t.is_accessible) {
+                topics.push(self.get_topic_detail(topic).await?);
+            }
+            courses.push(CourseSnapshot { detail, topics });
+        }
+        Ok(Snapshot { taken_at, courses })
+    }
+
+    /// Submits an answer for `task` through its upload form, then re-fetches the
+    /// topic and returns the answer as SPOT recorded it.
+    ///
+    /// A submission without text or a file is rejected before anything is sent.
+    pub async fn submit_task_answer(
+        &self,
+        task: &Task,
+        submission: AnswerSubmission,
+    ) -> Result<Answer> {
+        check_not_empty(&submission)?;
+        let task_id = task
+            .id
+            .ok_or_else(|| ScraperError::SubmissionFailed("task has no id".to_string()))?;
+        let form_info = task
+            .form
+            .as_ref()
+            .ok_or_else(|| ScraperError::SubmissionFailed("task has no upload form".to_string()))?;
+
+        let mut form = Form::new()
+            .text("_token", task.token.clone())
+            .text("id_tg", task_id.to_string());
+
+        if !submission.content.is_empty() {
+            let text_field = form_info.text_field.clone().ok_or_else(|| {
+                ScraperError::SubmissionFailed("task form does not accept text".to_string())
+            })?;
+            form = form.text(text_field, submission.content);
+        }
+
+        if let Some(file) = submission.file {
+            let file_field = form_info.file_field.clone().ok_or_else(|| {
+                ScraperError::SubmissionFailed("task form does not accept files".to_string())
+            })?;
+            let mut part = Part::bytes(file.bytes).file_name(file.file_name);
+            if let Some(mime_type) = file.mime_type {
+                part = part.mime_str(&mime_type)?;
+            }
+            form = form.part(file_field, part);
+        }
+
+        let response = self
+            .send(
+                self.client
+                    .post(self.absolute_url(&form_info.action))
+                    .multipart(form),
+            )
+            .await?;
+        if !self.is_spot_url(response.url()) {
+            return Err(ScraperError::SessionExpired);
+        }
+        error_for_status(response)?;
+
+        let topic = self.get_topic_detail(&task.topic_info()).await?;
+        topic
+            .tasks
+            .into_iter()
+            .find(|t| t.id == Some(task_id))
+            .and_then(|t| t.answer)
+            .ok_or_else(|| {
+                ScraperError::SubmissionFailed("SPOT did not record the answer".to_string())
+            })
+    }
+
+    /// Deletes the answer submitted for `task` using its `tugas_del` link, then
+    /// re-fetches the topic to check that SPOT removed it.
+    ///
+    /// Fails without touching SPOT if the deadline has passed, since a deleted
+    /// answer could not be submitted again, or if the answer has been graded.
+    pub async fn delete_answer(&self, task: &Task) -> Result<()> {
+        self.delete_answer_checked(task).await.map(|_| ())
+    }
+
+    /// Deletes the answer of `task` and returns the task as SPOT shows it now.
+    async fn delete_answer_checked(&self, task: &Task) -> Result<Task> {
+        check_answer_can_change(task)?;
+        let answer = task.answer.as_ref().ok_or_else(|| {
+            ScraperError::SubmissionFailed("task has no answer to delete".to_string())
         })?;
-        let topic_id = topic_info.id.ok_or_else(|| {
-            ScraperError::ParsingError("TopicInfo tidak memiliki id yang valid".to_string())
+        let href = answer.delete_href.as_deref().ok_or_else(|| {
+            ScraperError::SubmissionFailed("answer has no delete link".to_string())
         })?;
 
-        let html_content = self.get_html(href).await?;
-        parsers::topic_detail::parse_topic_detail_from_html(&html_content, topic_id, course_id)
+        let response = self.send(self.client.get(self.absolute_url(href))).await?;
+        if !self.is_spot_url(response.url()) {
+            return Err(ScraperError::SessionExpired);
+        }
+        error_for_status(response)?;
+
+        let topic = self.get_topic_detail(&task.topic_info()).await?;
+        let current = topic
+            .tasks
+            .into_iter()
+            .find(|t| t.id == task.id)
+            .ok_or_else(|| ScraperError::SubmissionFailed("the task disappeared".to_string()))?;
+        if current.answer.as_ref().is_some_and(|a| a.id == answer.id) {
+            return Err(ScraperError::SubmissionFailed(
+                "SPOT did not delete the answer".to_string(),
+            ));
+        }
+        Ok(current)
+    }
+
+    /// Replaces the current answer of `task` with `submission`.
+    ///
+    /// Fails without touching SPOT if the deadline has passed, the existing
+    /// answer has already been graded or `submission` is empty.
+    ///
+    /// SPOT keeps one answer per task, so the old answer has to be deleted
+    /// before the new one is submitted. If the submission then fails, the task
+    /// is left without an answer and [`ScraperError::ReplacementFailed`] is
+    /// returned; submit again before the deadline.
+    pub async fn replace_answer(
+        &self,
+        task: &Task,
+        submission: AnswerSubmission,
+    ) -> Result<Answer> {
+        check_answer_can_change(task)?;
+        check_not_empty(&submission)?;
+        if task.answer.is_none() {
+            return self.submit_task_answer(task, submission).await;
+        }
+        // The refreshed task carries the upload form as it is after deleting.
+        let task = self.delete_answer_checked(task).await?;
+        self.submit_task_answer(&task, submission)
+            .await
+            .map_err(|e| ScraperError::ReplacementFailed(Box::new(e)))
+    }
+}
+
+/// Rejects a submission with neither text nor a file.
+fn check_not_empty(submission: &AnswerSubmission) -> Result<()> {
+    if submission.content.trim().is_empty() && submission.file.is_none() {
+        return Err(ScraperError::SubmissionFailed("empty answer".to_string()));
+    }
+    Ok(())
+}
+
+/// Rejects changes to an answer after the deadline or once it is graded.
+fn check_answer_can_change(task: &Task) -> Result<()> {
+    if let Some(due_date) = task.due_date
+        && due_date < Utc::now()
+    {
+        return Err(ScraperError::DeadlinePassed(due_date));
+    }
+    if task.answer.as_ref().is_some_and(|a| a.is_graded) {
+        return Err(ScraperError::AnswerGraded);
+    }
+    Ok(())
+}
+
+/// Turns a non-success response into [`ScraperError::HttpStatus`].
+fn error_for_status(response: Throttled<'_>) -> Result<Throttled<'_>> {
+    let status = response.status();
+    if !status.is_success() {
+        return Err(ScraperError::HttpStatus {
+            status,
+            url: response.url().to_string(),
+            retry_after: retry::retry_after(response.headers()),
+        });
+    }
+    Ok(response)
+}
+
+/// Collects the metadata of a download from its response headers.
+fn download_info(response: &Response) -> Download {
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    Download {
+        url: response.url().to_string(),
+        content_type: header(CONTENT_TYPE).map(String::from),
+        file_name: header(CONTENT_DISPOSITION).and_then(content_disposition_file_name),
+        content_length: response.content_length(),
+        size: 0,
+    }
+}
+
+/// Extracts the file name from a `Content-Disposition` header, preferring the
+/// RFC 5987 `filename*` form over the plain `filename` parameter.
+fn content_disposition_file_name(header: &str) -> Option<String> {
+    let mut plain = None;
+    for param in header.split(';').map(str::trim) {
+        let Some((key, value)) = param.split_once('=') else {
+            continue;
+        };
+        match key.trim().to_ascii_lowercase().as_str() {
+            "filename*" => {
+                if let Some(encoded) = value.trim().splitn(3, '\'').nth(2)
+                    && let Ok(decoded) = percent_decode_str(encoded).decode_utf8()
+                {
+                    return Some(decoded.into_owned());
+                }
+            }
+            "filename" => plain = Some(value.trim().trim_matches('"').to_string()),
+            _ => {}
+        }
+    }
+    plain.filter(|name| !name.is_empty())
+}
+
+/// Keeps only the final component of a server-provided file name.
+fn sanitize_file_name(name: &str) -> String {
+    let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    match name {
+        "" | "." | ".." => "download".to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// The error for a page link that lacks what is needed to fetch it.
+fn missing(page: PageKind, problem: ParseProblem) -> ScraperError {
+    ScraperError::ParsingError {
+        page,
+        problem,
+        selector: None,
+        url: None,
+        excerpt: None,
     }
 }
@@ -0,0 +1,120 @@
+// src/crawl.rs
+
+//! Crawls every course and accessible topic concurrently, as a stream of events.
+
+use crate::client::SpotClient;
+use crate::error::{Result, ScraperError};
+use crate::models::{Course, DetailCourse, TopicDetail, TopicInfo};
+use futures::stream::{self, BoxStream, StreamExt};
+
+/// One step of [`SpotClient::crawl`].
+#[derive(Debug)]
+pub enum CrawlEvent {
+    /// A course from the dashboard. Every course is reported before any details.
+    CourseFound(Course),
+    /// A course page, including its locked topics.
+    CourseDetail(DetailCourse),
+    /// The course page could not be fetched or parsed; its topics are skipped.
+    CourseFailed {
+        course: Course,
+        error: ScraperError,
+    },
+    Topic {
+        course_id: u64,
+        topic: TopicDetail,
+    },
+    /// One topic page could not be fetched or parsed. The crawl carries on.
+    TopicFailed {
+        course_id: u64,
+        topic: TopicInfo,
+        error: ScraperError,
+    },
+}
+
+/// Work items flowing through the crawl pipeline.
+enum Job {
+    Done(Result<CrawlEvent>),
+    Course(Course),
+    Topic(u64, TopicInfo),
+}
+
+impl SpotClient {
+    /// Fetches every course, course page and accessible topic page, yielding
+    /// each as soon as it is parsed.
+    ///
+    /// Up to [`max_concurrency`](Self::max_concurrency) pages are fetched at
+    /// once, within the client's rate limits. Failures of single courses or
+    /// topics are reported as [`CrawlEvent::CourseFailed`] and
+    /// [`CrawlEvent::TopicFailed`]; only failing to load the course list ends
+    /// the stream, with an `Err`.
+    pub fn crawl(&self) -> BoxStream<'_, Result<CrawlEvent>> {
+        let concurrency = self.max_concurrency();
+
+        stream::once(self.get_courses())
+            .flat_map(|courses| {
+                let jobs = match courses {
+                    Ok(courses) => {
+                        let found: Vec<Job> = courses
+                            .iter()
+                            .map(|course| Job::Done(Ok(CrawlEvent::CourseFound(course.clone()))))
+                            .collect();
+                        found
+                            .into_iter()
+                            .chain(courses.into_iter().map(Job::Course))
+                            .collect()
+                    }
+                    Err(e) => vec![Job::Done(Err(e))],
+                };
+                stream::iter(jobs)
+            })
+            .map(move |job| async move {
+                match job {
+                    Job::Course(course) => self.crawl_course(course).await,
+                    job => vec![job],
+                }
+            })
+            .buffer_unordered(concurrency)
+            .flat_map(stream::iter)
+            .map(move |job| async move {
+                match job {
+                    Job::Topic(course_id, topic) => self.crawl_topic(course_id, topic).await,
+                    Job::Done(event) => event,
+                    Job::Course(_) => unreachable!("courses are expanded in the first stage"),
+                }
+            })
+            .buffer_unordered(concurrency)
+            .boxed()
+    }
+
+    /// Fetches a course page and queues its accessible topics.
+    async fn crawl_course(&self, course: Course) -> Vec<Job> {
+        match self.get_course_detail(&course).await {
+            Ok(detail) => {
+                let topics: Vec<Job> = detail
+                    .topics
+                    .iter()
+                    .filter(|topic| topic.is_accessible)
+                    .map(|topic| Job::Topic(course.id, topic.clone()))
+                    .collect();
+                std::iter::once(Job::Done(Ok(CrawlEvent::CourseDetail(detail))))
+                    .chain(topics)
+                    .collect()
+            }
+            Err(error) => vec![Job::Done(Ok(CrawlEvent::CourseFailed { course, error }))],
+        }
+    }
+
+    async fn crawl_topic(&self, course_id: u64, topic: TopicInfo) -> Result<CrawlEvent> {
+        Ok(match self.get_topic_detail(&topic).await {
+            Ok(detail) => CrawlEvent::Topic {
+                course_id,
+                topic: detail,
+            },
+            Err(error) => CrawlEvent::TopicFailed {
+                course_id,
+                topic,
+                error,
+            },
+        })
+    }
+}
@@ -0,0 +1,113 @@
+// src/dates.rs
+
+//! Parses the dates SPOT shows, in numeric or Indonesian long form.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
+
+/// Western Indonesia Time (UTC+7), used by SPOT and the default source zone.
+pub const WIB: FixedOffset = FixedOffset::east_opt(7 * 3600).unwrap();
+/// Central Indonesia Time (UTC+8).
+pub const WITA: FixedOffset = FixedOffset::east_opt(8 * 3600).unwrap();
+/// Eastern Indonesia Time (UTC+9).
+pub const WIT: FixedOffset = FixedOffset::east_opt(9 * 3600).unwrap();
+
+const MONTHS: &[(&[&str], u32)] = &[
+    (&["januari", "january", "jan"], 1),
+    (&["februari", "pebruari", "february", "feb", "peb"], 2),
+    (&["maret", "march", "mar"], 3),
+    (&["april", "apr"], 4),
+    (&["mei", "may"], 5),
+    (&["juni", "june", "jun"], 6),
+    (&["juli", "july", "jul"], 7),
+    (&["agustus", "august", "agu", "agt", "aug"], 8),
+    (&["september", "sept", "sep"], 9),
+    (&["oktober", "october", "okt", "oct"], 10),
+    (&["november", "nov", "nop"], 11),
+    (&["desember", "december", "des", "dec"], 12),
+];
+
+/// Parses a date and time written in `zone`, such as `04-09-2025 07:00`,
+/// `2025-09-04 07:00:00` or `Kamis, 4 September 2025 pukul 07.00 WIB`.
+///
+/// Words that are not month names are ignored, so labels like "Waktu Akses:"
+/// and weekday names may stay in. A trailing WIB, WITA or WIT overrides
+/// `zone`. A date without a time is taken as midnight.
+pub fn parse_datetime(text: &str, zone: FixedOffset) -> Option<DateTime<FixedOffset>> {
+    let mut zone = zone;
+    let mut numbers: Vec<&str> = Vec::new();
+    let mut month_name = None;
+
+    for token in text.split(|c: char| c.is_whitespace() || c == ',') {
+        let word = token
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        if word.chars().all(|c| c.is_alphabetic()) {
+            if let Some(month) = month_number(&word) {
+                month_name = Some((numbers.len(), month));
+            } else if let Some(offset) = zone_offset(&word) {
+                zone = offset;
+            }
+            continue;
+        }
+        numbers.extend(
+            token
+                .split(|c: char| !c.is_ascii_digit())
+                .filter(|n| !n.is_empty()),
+        );
+    }
+
+    let mut values: Vec<u32> = numbers
+        .iter()
+        .map(|n| n.parse().ok())
+        .collect::<Option<_>>()?;
+    let mut digits: Vec<usize> = numbers.iter().map(|n| n.len()).collect();
+    // "4 September 2025" puts the month between the day and the year.
+    if let Some((position, month)) = month_name {
+        values.insert(position, month);
+        digits.insert(position, 0);
+    }
+
+    // The year comes first or third and always has four digits, so a two
+    // digit year or a stray number in front is rejected rather than read
+    // as a wrong date.
+    let (date, time) = values.split_at_checked(3)?;
+    let (year, month, day) = if digits[0] == 4 {
+        (date[0], date[1], date[2])
+    } else if digits[2] == 4 {
+        (date[2], date[1], date[0])
+    } else {
+        return None;
+    };
+    let (hour, minute, second) = match time {
+        [] => (0, 0, 0),
+        [hour, minute] => (*hour, *minute, 0),
+        [hour, minute, second] => (*hour, *minute, *second),
+        _ => return None,
+    };
+
+    let naive =
+        NaiveDate::from_ymd_opt(year as i32, month, day)?.and_hms_opt(hour, minute, second)?;
+    zone.from_local_datetime(&naive).single()
+}
+
+/// The current time, in `zone`.
+pub(crate) fn now(zone: FixedOffset) -> DateTime<FixedOffset> {
+    Utc::now().with_timezone(&zone)
+}
+
+fn month_number(word: &str) -> Option<u32> {
+    MONTHS
+        .iter()
+        .find(|(names, _)| names.contains(&word))
+        .map(|(_, month)| *month)
+}
+
+fn zone_offset(word: &str) -> Option<FixedOffset> {
+    match word {
+        "wib" => Some(WIB),
+        "wita" => Some(WITA),
+        "wit" => Some(WIT),
+        "utc" | "gmt" => Some(FixedOffset::east_opt(0).unwrap()),
+        _ => None,
+    }
+}
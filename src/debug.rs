@@ -0,0 +1,67 @@
+// src/debug.rs
+
+//! Opt-in dumping of pages the client could not make sense of.
+
+use crate::error::{PageKind, ScraperError};
+use chrono::Utc;
+use std::path::PathBuf;
+
+/// A page that made a request fail, as passed to a [`PageDump`].
+#[derive(Debug, Clone, Copy)]
+pub struct FailedPage<'a> {
+    /// What the page was fetched for.
+    pub kind: PageKind,
+    pub url: &'a str,
+    pub html: &'a str,
+    pub error: &'a ScraperError,
+}
+
+/// Receives pages the client failed on, to find out later what SPOT or CAS
+/// actually answered. Set one with
+/// [`SpotClientBuilder::debug_dump`](crate::SpotClientBuilder::debug_dump).
+///
+/// Pages may contain personal data and session tokens; keep them private.
+pub trait PageDump: Send + Sync {
+    fn dump(&self, page: &FailedPage<'_>);
+}
+
+impl<F> PageDump for F
+where
+    F: Fn(&FailedPage<'_>) + Send + Sync,
+{
+    fn dump(&self, page: &FailedPage<'_>) {
+        self(page)
+    }
+}
+
+/// Writes every failed page to its own file in a directory, named after the
+/// time and the page kind, with the URL and error in a leading comment.
+#[derive(Debug, Clone)]
+pub struct DirectoryDump {
+    dir: PathBuf,
+}
+
+impl DirectoryDump {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl PageDump for DirectoryDump {
+    /// Failing to write a dump is ignored, so it never hides the original error.
+    fn dump(&self, page: &FailedPage<'_>) {
+        let name = format!(
+            "{}-{}.html",
+            Utc::now().format("%Y%m%dT%H%M%S%.6fZ"),
+            page.kind.as_str()
+        );
+        let contents = format!(
+            "<!--\n  url: {}\n  error: {}\n-->\n{}",
+            page.url,
+            page.error.to_string().replace("--", "- -"),
+            page.html
+        );
+        let _ = std::fs::create_dir_all(&self.dir)
+            .and_then(|()| std::fs::write(self.dir.join(name), contents));
+    }
+}
@@ -0,0 +1,282 @@
+// src/diff.rs
+
+//! Compares two [`Snapshot`]s of the course tree and reports what changed.
+
+use crate::error::{Result, ScraperError};
+use crate::models::{Answer, CourseSnapshot, Snapshot, Task, TopicDetail};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Something that changed between two snapshots.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChangeEvent {
+    /// A course that was not in the old snapshot. Its topics and tasks are not
+    /// reported separately.
+    CourseAdded { course_id: u64, name: String },
+    TopicAdded {
+        course_id: u64,
+        /// `None` for a topic that is still locked.
+        topic_id: Option<u64>,
+        is_accessible: bool,
+    },
+    /// A known topic that was locked and is now open.
+    TopicAccessible { course_id: u64, topic_id: u64 },
+    TaskAdded {
+        course_id: u64,
+        topic_id: u64,
+        task: TaskRef,
+        due_date: Option<DateTime<FixedOffset>>,
+    },
+    DueDateChanged {
+        course_id: u64,
+        topic_id: u64,
+        task: TaskRef,
+        old: Option<DateTime<FixedOffset>>,
+        new: Option<DateTime<FixedOffset>>,
+    },
+    /// An answer was graded for the first time.
+    AnswerGraded {
+        course_id: u64,
+        topic_id: u64,
+        task: TaskRef,
+        score: f32,
+        lecturer_notes: String,
+    },
+    /// The score of an already graded answer changed.
+    ScoreChanged {
+        course_id: u64,
+        topic_id: u64,
+        task: TaskRef,
+        old: f32,
+        new: f32,
+    },
+    /// The lecturer's notes on an already graded answer changed.
+    LecturerNotesChanged {
+        course_id: u64,
+        topic_id: u64,
+        task: TaskRef,
+        old: String,
+        new: String,
+    },
+}
+
+/// Identifies the task an event is about.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TaskRef {
+    pub id: Option<u64>,
+    pub title: String,
+}
+
+impl From<&Task> for TaskRef {
+    fn from(task: &Task) -> Self {
+        TaskRef {
+            id: task.id,
+            title: task.title.clone(),
+        }
+    }
+}
+
+/// Lists the changes from `old` to `new`, in course, topic and task order of `new`.
+pub fn diff(old: &Snapshot, new: &Snapshot) -> Vec<ChangeEvent> {
+    let mut events = Vec::new();
+    for course in &new.courses {
+        let course_id = course.detail.course_info.id;
+        match old
+            .courses
+            .iter()
+            .find(|c| c.detail.course_info.id == course_id)
+        {
+            Some(old_course) => diff_course(old_course, course, &mut events),
+            None => events.push(ChangeEvent::CourseAdded {
+                course_id,
+                name: course.detail.course_info.name.clone(),
+            }),
+        }
+    }
+    events
+}
+
+/// Like [`diff`], but for snapshots serialized as JSON.
+pub fn diff_json(old: &str, new: &str) -> Result<Vec<ChangeEvent>> {
+    let parse = |json: &str| {
+        serde_json::from_str::<Snapshot>(json)
+            .map_err(|e| ScraperError::InvalidSnapshot(e.to_string()))
+    };
+    Ok(diff(&parse(old)?, &parse(new)?))
+}
+
+fn diff_course(old: &CourseSnapshot, new: &CourseSnapshot, events: &mut Vec<ChangeEvent>) {
+    let course_id = new.detail.course_info.id;
+
+    for (index, topic) in new.detail.topics.iter().enumerate() {
+        // Locked topics have no link and hence no id, so fall back to the
+        // topic's position, which SPOT keeps stable.
+        let before = match topic.id {
+            Some(_) => old.detail.topics.iter().find(|t| t.id == topic.id),
+            None => None,
+        }
+        .or_else(|| old.detail.topics.get(index).filter(|t| t.id.is_none()));
+
+        match before {
+            None => events.push(ChangeEvent::TopicAdded {
+                course_id,
+                topic_id: topic.id,
+                is_accessible: topic.is_accessible,
+            }),
+            Some(before) if !before.is_accessible && topic.is_accessible => {
+                if let Some(topic_id) = topic.id {
+                    events.push(ChangeEvent::TopicAccessible {
+                        course_id,
+                        topic_id,
+                    })
+                }
+            }
+            Some(_) => {}
+        }
+    }
+
+    for topic in &new.topics {
+        let old_topic = old.topics.iter().find(|t| t.id == topic.id);
+        diff_tasks(course_id, old_topic, topic, events);
+    }
+}
+
+fn diff_tasks(
+    course_id: u64,
+    old: Option<&TopicDetail>,
+    new: &TopicDetail,
+    events: &mut Vec<ChangeEvent>,
+) {
+    let topic_id = new.id;
+    let old_tasks = old.map(|t| t.tasks.as_slice()).unwrap_or_default();
+
+    for task in &new.tasks {
+        let Some(before) = old_tasks.iter().find(|t| same_task(t, task)) else {
+            events.push(ChangeEvent::TaskAdded {
+                course_id,
+                topic_id,
+                task: task.into(),
+                due_date: task.due_date,
+            });
+            continue;
+        };
+
+        if before.due_date != task.due_date {
+            events.push(ChangeEvent::DueDateChanged {
+                course_id,
+                topic_id,
+                task: task.into(),
+                old: before.due_date,
+                new: task.due_date,
+            });
+        }
+        if let Some(answer) = &task.answer {
+            diff_answer(
+                course_id,
+                topic_id,
+                task,
+                before.answer.as_ref(),
+                answer,
+                events,
+            );
+        }
+    }
+}
+
+fn diff_answer(
+    course_id: u64,
+    topic_id: u64,
+    task: &Task,
+    old: Option<&Answer>,
+    new: &Answer,
+    events: &mut Vec<ChangeEvent>,
+) {
+    if !new.is_graded {
+        return;
+    }
+    let Some(old) = old.filter(|a| a.is_graded) else {
+        events.push(ChangeEvent::AnswerGraded {
+            course_id,
+            topic_id,
+            task: task.into(),
+            score: new.score,
+            lecturer_notes: new.lecturer_notes.clone(),
+        });
+        return;
+    };
+
+    if old.score != new.score {
+        events.push(ChangeEvent::ScoreChanged {
+            course_id,
+            topic_id,
+            task: task.into(),
+            old: old.score,
+            new: new.score,
+        });
+    }
+    if old.lecturer_notes != new.lecturer_notes {
+        events.push(ChangeEvent::LecturerNotesChanged {
+            course_id,
+            topic_id,
+            task: task.into(),
+            old: old.lecturer_notes.clone(),
+            new: new.lecturer_notes.clone(),
+        });
+    }
+}
+
+/// Matches tasks by id, or by title when SPOT did not expose an id.
+fn same_task(a: &Task, b: &Task) -> bool {
+    match (a.id, b.id) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.title == b.title,
+    }
+}
+
+impl fmt::Display for ChangeEvent {
+    /// A one-line summary, as sent to chat webhooks.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let date = |date: &Option<DateTime<FixedOffset>>| {
+            date.map(|d| d.format("%d-%m-%Y %H:%M").to_string())
+                .unwrap_or_else(|| "-".to_string())
+        };
+        match self {
+            ChangeEvent::CourseAdded { name, .. } => write!(f, "New course: {}", name),
+            ChangeEvent::TopicAdded {
+                course_id,
+                topic_id: Some(topic_id),
+                ..
+            } => write!(f, "New topic {} in course {}", topic_id, course_id),
+            ChangeEvent::TopicAdded { course_id, .. } => {
+                write!(f, "New locked topic in course {}", course_id)
+            }
+            ChangeEvent::TopicAccessible {
+                course_id,
+                topic_id,
+            } => write!(f, "Topic {} in course {} is now open", topic_id, course_id),
+            ChangeEvent::TaskAdded { task, due_date, .. } => {
+                write!(f, "New task \"{}\", due {}", task.title, date(due_date))
+            }
+            ChangeEvent::DueDateChanged { task, old, new, .. } => write!(
+                f,
+                "Due date of \"{}\" moved from {} to {}",
+                task.title,
+                date(old),
+                date(new)
+            ),
+            ChangeEvent::AnswerGraded { task, score, .. } => {
+                write!(f, "\"{}\" was graded: {}", task.title, score)
+            }
+            ChangeEvent::ScoreChanged { task, old, new, .. } => write!(
+                f,
+                "Score of \"{}\" changed from {} to {}",
+                task.title, old, new
+            ),
+            ChangeEvent::LecturerNotesChanged { task, new, .. } => {
+                write!(f, "New lecturer notes on \"{}\": {}", task.title, new)
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+// src/embed.rs
+
+//! Recognizes links to video, document and meeting services used in SPOT materials.
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+/// File extensions that mark a link as a downloadable file.
+const FILE_EXTENSIONS: &[&str] = &[
+    "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "zip", "rar", "7z", "txt", "csv", "mp3",
+    "mp4",
+];
+
+/// A link or frame in a topic material, classified by the service it points to.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Embed {
+    pub url: String,
+    pub kind: EmbedKind,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EmbedKind {
+    YouTube {
+        video_id: String,
+    },
+    Vimeo {
+        video_id: String,
+    },
+    GoogleDriveFile {
+        file_id: String,
+    },
+    GoogleDriveFolder {
+        folder_id: String,
+    },
+    GoogleDocs {
+        document_id: String,
+    },
+    GoogleSheets {
+        spreadsheet_id: String,
+    },
+    GoogleSlides {
+        presentation_id: String,
+    },
+    /// A Google Form, identified by its form id or its `forms.gle` short code.
+    GoogleForms {
+        form_id: String,
+    },
+    GoogleMeet {
+        meeting_code: String,
+    },
+    Zoom {
+        meeting_id: Option<String>,
+        passcode: Option<String>,
+    },
+    /// A Word, Excel or PowerPoint document on OneDrive, SharePoint or Office Online.
+    MicrosoftOffice,
+    /// A link straight to a file, such as a PDF or SPOT's own download links.
+    DirectFile {
+        extension: Option<String>,
+    },
+}
+
+impl EmbedKind {
+    pub fn is_video(&self) -> bool {
+        matches!(self, EmbedKind::YouTube { .. } | EmbedKind::Vimeo { .. })
+    }
+}
+
+/// The host of the production SPOT server.
+const SPOT_HOST: &str = "spot.upi.edu";
+
+/// Classifies an absolute URL, returning `None` for links to unknown services.
+///
+/// Links into SPOT's download and upload paths only count as files on the
+/// production SPOT host; see [`classify_spot_url`] for other servers.
+pub fn classify_url(url: &str) -> Option<EmbedKind> {
+    classify_spot_url(url, SPOT_HOST)
+}
+
+/// Like [`classify_url`], for materials served by the SPOT server at `spot_host`.
+///
+/// Paths such as `/download/` and `/uploads/` are only taken as files on
+/// `spot_host`, so pages on other sites are never mistaken for SPOT downloads
+/// and fetched with the session attached.
+pub fn classify_spot_url(url: &str, spot_host: &str) -> Option<EmbedKind> {
+    let url = Url::parse(url).ok()?;
+    let on_spot = url
+        .host_str()
+        .is_some_and(|host| host.eq_ignore_ascii_case(spot_host));
+    let host = url.host_str()?.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|s| s.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+            .filter(|v| !v.is_empty())
+    };
+    let segment_after = |marker: &str| {
+        segments
+            .iter()
+            .position(|s| *s == marker)
+            .and_then(|i| segments.get(i + 1))
+            .map(|s| s.to_string())
+    };
+
+    let kind = match host {
+        "youtu.be" => EmbedKind::YouTube {
+            video_id: segments.first()?.to_string(),
+        },
+        "youtube.com" | "m.youtube.com" | "youtube-nocookie.com" => {
+            let video_id = match segments.first() {
+                Some(&"watch") => query("v")?,
+                Some(&"embed" | &"shorts" | &"live" | &"v") => segments.get(1)?.to_string(),
+                _ => return None,
+            };
+            EmbedKind::YouTube { video_id }
+        }
+        "vimeo.com" | "player.vimeo.com" => EmbedKind::Vimeo {
+            video_id: segments
+                .iter()
+                .find(|s| s.chars().all(|c| c.is_ascii_digit()))?
+                .to_string(),
+        },
+        "drive.google.com" => match segments.first() {
+            Some(&"file") => EmbedKind::GoogleDriveFile {
+                file_id: segment_after("d")?,
+            },
+            Some(&"drive") => EmbedKind::GoogleDriveFolder {
+                folder_id: segment_after("folders")?,
+            },
+            Some(&"open" | &"uc") => EmbedKind::GoogleDriveFile {
+                file_id: query("id")?,
+            },
+            _ => return None,
+        },
+        "docs.google.com" => {
+            // Links from shared accounts look like /a/<domain>/document/d/<id>.
+            let app = segments.iter().position(|s| {
+                matches!(*s, "document" | "spreadsheets" | "presentation" | "forms")
+            })?;
+            let rest = &segments[app + 1..];
+            let id = match rest {
+                ["d", "e", id, ..] | ["d", id, ..] => id.to_string(),
+                _ => return None,
+            };
+            match segments[app] {
+                "document" => EmbedKind::GoogleDocs { document_id: id },
+                "spreadsheets" => EmbedKind::GoogleSheets { spreadsheet_id: id },
+                "presentation" => EmbedKind::GoogleSlides {
+                    presentation_id: id,
+                },
+                _ => EmbedKind::GoogleForms { form_id: id },
+            }
+        }
+        "forms.gle" => EmbedKind::GoogleForms {
+            form_id: segments.first()?.to_string(),
+        },
+        "meet.google.com" => EmbedKind::GoogleMeet {
+            meeting_code: segments.first()?.to_string(),
+        },
+        host if host == "zoom.us" || host.ends_with(".zoom.us") => EmbedKind::Zoom {
+            meeting_id: segment_after("j").or_else(|| segment_after("w")),
+            passcode: query("pwd"),
+        },
+        host if host == "1drv.ms"
+            || host == "onedrive.live.com"
+            || host.ends_with(".sharepoint.com")
+            || host == "office.com"
+            || host.ends_with(".office.com")
+            || host.ends_with(".officeapps.live.com") =>
+        {
+            EmbedKind::MicrosoftOffice
+        }
+        _ => {
+            let path = url.path().to_ascii_lowercase();
+            let extension = path
+                .rsplit('/')
+                .next()
+                .and_then(|name| name.rsplit_once('.'))
+                .map(|(_, ext)| ext.to_string())
+                .filter(|ext| FILE_EXTENSIONS.contains(&ext.as_str()));
+            let spot_file =
+                on_spot && (path.contains("/download/") || path.starts_with("/uploads/"));
+            if extension.is_some() || spot_file {
+                EmbedKind::DirectFile { extension }
+            } else {
+                return None;
+            }
+        }
+    };
+    Some(kind)
+}
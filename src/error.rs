@@ -1,4 +1,7 @@
 // src/error.rs
+use chrono::{DateTime, FixedOffset};
+use reqwest::StatusCode;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,20 +9,341 @@ pub enum ScraperError {
     #[error("Request to SPOT failed: {0}")]
     RequestError(#[from] reqwest::Error),
 
-    #[error("Failed to parse HTML: {0}")]
-    ParsingError(String),
+    /// SPOT answered with a non-success status, e.g. 404 for a removed topic
+    /// or 502 while it is overloaded.
+    #[error("SPOT responded with {status} for {url}")]
+    HttpStatus {
+        status: StatusCode,
+        url: String,
+        /// How long SPOT asked clients to wait, from its `Retry-After` header.
+        retry_after: Option<Duration>,
+    },
+
+    /// A page was found but did not contain what SPOT usually puts there.
+    #[error("Could not parse the {page} page: {problem}{}", at(.url))]
+    ParsingError {
+        page: PageKind,
+        problem: ParseProblem,
+        /// The selector whose match did not make sense, if one was involved.
+        selector: Option<&'static str>,
+        /// The page address; `None` when the HTML was parsed without a client.
+        url: Option<String>,
+        /// The HTML around the problem, shortened.
+        excerpt: Option<String>,
+    },
 
     #[error("The SPOT session appears to have expired")]
     SessionExpired,
 
-    #[error("Authentication failed. Please check your credentials.")]
-    AuthenticationFailed,
+    /// CAS did not send the client back to SPOT after logging in.
+    #[error(
+        "Login failed: {reason}{} (final URL: {url})",
+        .message.as_deref().map(|m| format!(": \"{}\"", m)).unwrap_or_default()
+    )]
+    AuthenticationFailed {
+        reason: LoginFailure,
+        /// The error message CAS showed, if any.
+        message: Option<String>,
+        /// Where the login ended up.
+        url: String,
+    },
 
     #[error("Could not find the login CSRF token on the page")]
     TokenNotFound,
 
-    #[error("Could not find required element on the page: {0}")]
-    ElementNotFound(String),
+    /// A selector matched nothing, usually because SPOT changed its markup.
+    #[error("Could not find {element} on the {page} page (selector `{selector}`){}", at(.url))]
+    ElementNotFound {
+        page: PageKind,
+        element: PageElement,
+        selector: &'static str,
+        /// The page address; `None` when the HTML was parsed without a client.
+        url: Option<String>,
+        /// The HTML that was searched, shortened.
+        excerpt: Option<String>,
+    },
+
+    #[error("Could not submit the task answer: {0}")]
+    SubmissionFailed(String),
+
+    #[error("The task deadline ({0}) has passed")]
+    DeadlinePassed(DateTime<FixedOffset>),
+
+    #[error("The answer has already been graded and can no longer be changed")]
+    AnswerGraded,
+
+    /// [`replace_answer`](crate::SpotClient::replace_answer) deleted the old
+    /// answer but could not submit the new one, so the task has no answer now.
+    #[error("The old answer was deleted but the new one could not be submitted: {0}")]
+    ReplacementFailed(#[source] Box<ScraperError>),
+
+    #[error("Could not save or restore the session: {0}")]
+    SessionError(String),
+
+    #[error("Invalid snapshot: {0}")]
+    InvalidSnapshot(String),
+
+    #[error("Local archive error: {0}")]
+    StorageError(String),
+
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Invalid client configuration: {0}")]
+    InvalidConfig(String),
+}
+
+/// Why CAS refused a login, as diagnosed from the page it answered with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginFailure {
+    InvalidCredentials,
+    AccountLocked,
+    /// CAS asks for a captcha, usually after several failed attempts.
+    CaptchaRequired,
+    /// CAS is down for maintenance.
+    Maintenance,
+    /// The login was sent somewhere other than CAS or SPOT, e.g. a password
+    /// change page.
+    UnexpectedRedirect,
+    /// CAS showed its login form again without saying why.
+    Unknown,
+}
+
+impl std::fmt::Display for LoginFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LoginFailure::InvalidCredentials => "wrong NIM or password",
+            LoginFailure::AccountLocked => "the account is locked",
+            LoginFailure::CaptchaRequired => "CAS requires a captcha",
+            LoginFailure::Maintenance => "CAS is under maintenance",
+            LoginFailure::UnexpectedRedirect => "CAS redirected to an unexpected page",
+            LoginFailure::Unknown => "CAS did not say why",
+        })
+    }
+}
+
+/// The kinds of page the client fetches and parses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PageKind {
+    /// The CAS login form.
+    Login,
+    /// The student dashboard at `/mhs`, with the profile and course list.
+    Dashboard,
+    CourseDetail,
+    Rps,
+    TopicDetail,
+}
+
+impl PageKind {
+    /// A short identifier, used e.g. in file names of dumped pages.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PageKind::Login => "login",
+            PageKind::Dashboard => "dashboard",
+            PageKind::CourseDetail => "course_detail",
+            PageKind::Rps => "rps",
+            PageKind::TopicDetail => "topic_detail",
+        }
+    }
+
+    fn name(self, language: Language) -> &'static str {
+        match (self, language) {
+            (PageKind::Login, Language::English) => "login",
+            (PageKind::Login, Language::Indonesian) => "login",
+            (PageKind::Dashboard, Language::English) => "dashboard",
+            (PageKind::Dashboard, Language::Indonesian) => "dasbor",
+            (PageKind::CourseDetail, Language::English) => "course",
+            (PageKind::CourseDetail, Language::Indonesian) => "mata kuliah",
+            (PageKind::Rps, _) => "RPS",
+            (PageKind::TopicDetail, Language::English) => "topic",
+            (PageKind::TopicDetail, Language::Indonesian) => "topik",
+        }
+    }
+}
+
+impl std::fmt::Display for PageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name(Language::English))
+    }
+}
+
+/// What [`ScraperError::ElementNotFound`] was looking for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageElement {
+    ProfileText,
+    CourseRows,
+    CourseLink,
+    RpsContent,
+}
+
+impl PageElement {
+    fn name(self, language: Language) -> &'static str {
+        match (self, language) {
+            (PageElement::ProfileText, Language::English) => "the profile text",
+            (PageElement::ProfileText, Language::Indonesian) => "teks profil",
+            (PageElement::CourseRows, Language::English) => "any course rows",
+            (PageElement::CourseRows, Language::Indonesian) => "baris mata kuliah",
+            (PageElement::CourseLink, Language::English) => "the course link",
+            (PageElement::CourseLink, Language::Indonesian) => "tautan mata kuliah",
+            (PageElement::RpsContent, Language::English) => "any RPS content",
+            (PageElement::RpsContent, Language::Indonesian) => "isi RPS",
+        }
+    }
+}
+
+impl std::fmt::Display for PageElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name(Language::English))
+    }
+}
+
+/// What was wrong with the page in a [`ScraperError::ParsingError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseProblem {
+    /// The profile text has no NIM.
+    MissingNim,
+    /// The profile text has a NIM but no name.
+    MissingName,
+    /// A course table row has fewer cells than expected.
+    TooFewColumns,
+    /// The RPS or topic has no link to its page.
+    MissingHref,
+    /// The topic does not say which course it belongs to.
+    MissingCourseId,
+    MissingTopicId,
+}
+
+impl ParseProblem {
+    fn describe(self, language: Language) -> &'static str {
+        match (self, language) {
+            (ParseProblem::MissingNim, Language::English) => "the profile has no NIM",
+            (ParseProblem::MissingNim, Language::Indonesian) => "profil tidak memuat NIM",
+            (ParseProblem::MissingName, Language::English) => "the profile has no name",
+            (ParseProblem::MissingName, Language::Indonesian) => "profil tidak memuat nama",
+            (ParseProblem::TooFewColumns, Language::English) => "a table row has too few columns",
+            (ParseProblem::TooFewColumns, Language::Indonesian) => {
+                "baris tabel tidak memiliki kolom yang cukup"
+            }
+            (ParseProblem::MissingHref, Language::English) => "no link to the page",
+            (ParseProblem::MissingHref, Language::Indonesian) => "tidak ada tautan ke halaman",
+            (ParseProblem::MissingCourseId, Language::English) => "no course id",
+            (ParseProblem::MissingCourseId, Language::Indonesian) => "tidak ada id mata kuliah",
+            (ParseProblem::MissingTopicId, Language::English) => "no topic id",
+            (ParseProblem::MissingTopicId, Language::Indonesian) => "tidak ada id topik",
+        }
+    }
+}
+
+impl std::fmt::Display for ParseProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.describe(Language::English))
+    }
+}
+
+/// Languages [`ScraperError::localized`] can render messages in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    /// The language of the `Display` messages.
+    #[default]
+    English,
+    Indonesian,
+}
+
+fn at(url: &Option<String>) -> String {
+    url.as_deref()
+        .map(|url| format!(" at {}", url))
+        .unwrap_or_default()
+}
+
+impl ScraperError {
+    /// The message in `language`. Only parsing errors are translated so far;
+    /// every other error renders as its English `Display` message.
+    pub fn localized(&self, language: Language) -> String {
+        if language == Language::English {
+            return self.to_string();
+        }
+        let at = |url: &Option<String>| {
+            url.as_deref()
+                .map(|url| format!(" di {}", url))
+                .unwrap_or_default()
+        };
+        match self {
+            ScraperError::ParsingError {
+                page, problem, url, ..
+            } => format!(
+                "Gagal membaca halaman {}: {}{}",
+                page.name(language),
+                problem.describe(language),
+                at(url)
+            ),
+            ScraperError::ElementNotFound {
+                page,
+                element,
+                selector,
+                url,
+                ..
+            } => format!(
+                "Tidak menemukan {} di halaman {} (selector `{}`){}",
+                element.name(language),
+                page.name(language),
+                selector,
+                at(url)
+            ),
+            other => other.to_string(),
+        }
+    }
+
+    /// Fills in the page address of a parsing error that does not have one.
+    pub(crate) fn with_url(mut self, page_url: &str) -> Self {
+        if let ScraperError::ParsingError { url, .. } | ScraperError::ElementNotFound { url, .. } =
+            &mut self
+            && url.is_none()
+        {
+            *url = Some(page_url.to_string());
+        }
+        self
+    }
+
+    /// Whether the same request might succeed if sent again: timeouts, failed
+    /// connections, bodies cut off halfway, 5xx responses, 408 and 429. Other
+    /// failures while sending a request are not retried.
+    ///
+    /// Expired sessions are not retryable here; the client renews them
+    /// separately when it has credentials.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ScraperError::RequestError(e) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.is_body()
+                    || e.status().is_some_and(is_retryable_status)
+            }
+            ScraperError::HttpStatus { status, .. } => is_retryable_status(*status),
+            ScraperError::IoError(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::Interrupted
+            ),
+            _ => false,
+        }
+    }
+
+    /// The HTTP status SPOT answered with, if the error came from one.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ScraperError::RequestError(e) => e.status(),
+            ScraperError::HttpStatus { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
 }
 
 pub type Result<T> = std::result::Result<T, ScraperError>;
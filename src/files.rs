@@ -0,0 +1,101 @@
+// src/files.rs
+
+//! Helpers for files the crate rewrites in place, such as state, sessions
+//! and downloads.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+
+/// Replaces the file at `path` with `contents` so that a crash mid-write
+/// leaves either the old or the new file, never a truncated one.
+///
+/// The contents go to a temporary file next to `path` first, which is then
+/// renamed over it.
+pub(crate) async fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    write_via_temp(path, contents, false).await
+}
+
+/// Like [`write_atomic`], but on unix the file can only be read and written
+/// by its owner (mode 0600), whatever the process umask.
+pub(crate) async fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    write_via_temp(path, contents, true).await
+}
+
+async fn write_via_temp(path: &Path, contents: &[u8], private: bool) -> io::Result<()> {
+    let mut file = TempFile::create(path, private).await?;
+    if let Err(e) = file.write_all(contents).await {
+        file.discard().await;
+        return Err(e);
+    }
+    file.persist().await
+}
+
+/// A file written next to its destination, which only appears there once
+/// [`persist`](Self::persist) renames it over the destination.
+pub(crate) struct TempFile {
+    file: File,
+    temp: PathBuf,
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Creates the temporary file for `path`. With `private`, on unix it can
+    /// only be read and written by its owner (mode 0600).
+    pub(crate) async fn create(path: &Path, private: bool) -> io::Result<Self> {
+        let temp = temp_path(path);
+        // A leftover from a crash would keep its old permissions if reused.
+        let _ = tokio::fs::remove_file(&temp).await;
+
+        let mut options = tokio::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        if private {
+            options.mode(0o600);
+        }
+        #[cfg(not(unix))]
+        let _ = private;
+
+        Ok(Self {
+            file: options.open(&temp).await?,
+            temp,
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub(crate) async fn write_all(&mut self, contents: &[u8]) -> io::Result<()> {
+        self.file.write_all(contents).await
+    }
+
+    /// Syncs the file to disk and renames it over the destination. The
+    /// temporary file is removed if that fails.
+    pub(crate) async fn persist(self) -> io::Result<()> {
+        let Self { file, temp, path } = self;
+        let result = async {
+            file.sync_all().await?;
+            drop(file);
+            tokio::fs::rename(&temp, &path).await
+        }
+        .await;
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&temp).await;
+        }
+        result
+    }
+
+    /// Removes the temporary file, leaving the destination untouched.
+    pub(crate) async fn discard(self) {
+        drop(self.file);
+        let _ = tokio::fs::remove_file(&self.temp).await;
+    }
+}
+
+/// `dir/.name.tmp` for `dir/name`, so the rename stays on one file system.
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.tmp", name))
+}
@@ -0,0 +1,243 @@
+// src/ical.rs
+
+//! Exports task deadlines and topic access times as an iCalendar (`.ics`) feed.
+
+use crate::models::{CourseSnapshot, Snapshot, Task, TopicInfo};
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+
+const DEFAULT_BASE_URL: &str = "https://spot.upi.edu";
+const DEFAULT_CALENDAR_NAME: &str = "SPOT UPI";
+const UID_DOMAIN: &str = "spot.upi.edu";
+
+/// Builds an `.ics` feed from a [`Snapshot`].
+///
+/// Every event has a UID derived from the course, topic and task ids, so
+/// importing a newer feed updates existing events instead of duplicating them.
+#[derive(Debug, Clone)]
+pub struct CalendarExporter {
+    base_url: String,
+    name: String,
+    reminders: Vec<Duration>,
+    topic_access_times: bool,
+}
+
+impl Default for CalendarExporter {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            name: DEFAULT_CALENDAR_NAME.to_string(),
+            reminders: Vec::new(),
+            topic_access_times: false,
+        }
+    }
+}
+
+impl CalendarExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the SPOT base URL used to turn relative topic links into absolute ones.
+    pub fn base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn calendar_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Adds a reminder `before` each task's due date. Can be called several times.
+    pub fn reminder(mut self, before: Duration) -> Self {
+        self.reminders.push(before);
+        self
+    }
+
+    /// Also adds an event for each topic's access time. Off by default.
+    pub fn topic_access_times(mut self, enabled: bool) -> Self {
+        self.topic_access_times = enabled;
+        self
+    }
+
+    pub fn export(&self, snapshot: &Snapshot) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//spot-scraper//SPOT UPI//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+            format!("X-WR-CALNAME:{}", escape_text(&self.name)),
+        ];
+        let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+        for course in &snapshot.courses {
+            if self.topic_access_times {
+                for (index, topic) in course.detail.topics.iter().enumerate() {
+                    self.topic_event(course, index + 1, topic, &stamp, &mut lines);
+                }
+            }
+            for topic in &course.topics {
+                for task in &topic.tasks {
+                    let link = self.link(&topic.href);
+                    self.task_event(course, task, &link, &stamp, &mut lines);
+                }
+            }
+        }
+
+        lines.push("END:VCALENDAR".to_string());
+        lines.iter().map(|line| fold(line)).collect()
+    }
+
+    fn task_event(
+        &self,
+        course: &CourseSnapshot,
+        task: &Task,
+        link: &str,
+        stamp: &str,
+        lines: &mut Vec<String>,
+    ) {
+        let Some(due) = task.due_date else {
+            return;
+        };
+        let start = task.start_date.filter(|start| *start <= due).unwrap_or(due);
+        let course_name = &course.detail.course_info.name;
+        let task_key = match task.id {
+            Some(id) => id.to_string(),
+            None => format!("{:016x}", fnv1a(&task.title)),
+        };
+
+        let mut description = format!("{}\n\n", course_name);
+        let text = task.description_plain_text();
+        if !text.is_empty() {
+            description.push_str(&text);
+            description.push_str("\n\n");
+        }
+        description.push_str(link);
+
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!(
+                "UID:task-{}-{}-{}@{}",
+                task.course_id, task.topic_id, task_key, UID_DOMAIN
+            ),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART:{}", utc(start)),
+            format!("DTEND:{}", utc(due)),
+            format!(
+                "SUMMARY:{}",
+                escape_text(&format!("{}: {}", course_name, task.title))
+            ),
+            format!("DESCRIPTION:{}", escape_text(&description)),
+            format!("URL:{}", link),
+            format!(
+                "CATEGORIES:{}",
+                escape_text(&course.detail.course_info.code)
+            ),
+        ]);
+        for before in &self.reminders {
+            lines.extend([
+                "BEGIN:VALARM".to_string(),
+                "ACTION:DISPLAY".to_string(),
+                format!("TRIGGER;RELATED=END:-PT{}M", before.num_minutes().max(0)),
+                format!("DESCRIPTION:{}", escape_text(&task.title)),
+                "END:VALARM".to_string(),
+            ]);
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+
+    fn topic_event(
+        &self,
+        course: &CourseSnapshot,
+        number: usize,
+        topic: &TopicInfo,
+        stamp: &str,
+        lines: &mut Vec<String>,
+    ) {
+        let Some(access_time) = topic.access_time else {
+            return;
+        };
+        let course_info = &course.detail.course_info;
+        // Locked topics have no link yet, so point at the course page instead.
+        let link = self.link(topic.href.as_deref().unwrap_or(&course_info.href));
+        // Keyed by id, so inserting or moving a topic keeps the other events.
+        // Locked topics do not expose their id yet and fall back to position.
+        let key = match topic.id {
+            Some(id) => id.to_string(),
+            None => format!("locked-{}", number),
+        };
+
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:topic-{}-{}@{}", course_info.id, key, UID_DOMAIN),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART:{}", utc(access_time)),
+            format!("DTEND:{}", utc(access_time)),
+            format!(
+                "SUMMARY:{}",
+                escape_text(&format!("{}: topic {} opens", course_info.name, number))
+            ),
+            format!("DESCRIPTION:{}", escape_text(&link)),
+            format!("URL:{}", link),
+            format!("CATEGORIES:{}", escape_text(&course_info.code)),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+
+    fn link(&self, href: &str) -> String {
+        if href.starts_with("http://") || href.starts_with("https://") {
+            href.to_string()
+        } else {
+            format!("{}/{}", self.base_url, href.trim_start_matches('/'))
+        }
+    }
+}
+
+/// Formats a time as a UTC iCalendar date-time.
+fn utc(time: DateTime<FixedOffset>) -> String {
+    time.with_timezone(&Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Escapes a TEXT value as required by RFC 5545.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Folds a content line into CRLF-terminated chunks of at most 75 octets,
+/// never splitting a UTF-8 character.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// A hash that, unlike `std`'s, is guaranteed to stay the same across builds.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
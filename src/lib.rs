@@ -1,10 +1,48 @@
 // Declare all our modules
+mod auth;
 mod client;
+mod crawl;
+mod dates;
+mod debug;
+mod diff;
+mod embed;
 mod error;
+mod files;
+mod ical;
 mod models;
+// Public only with the `bench` feature, so the benchmarks in `benches/` can
+// call the parsers directly.
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod parsers;
+#[cfg(not(feature = "bench"))]
 mod parsers;
+mod render;
+mod retry;
+mod session;
+#[cfg(feature = "storage")]
+mod storage;
+mod tasks;
+mod throttle;
+mod watch;
 
 // Publicly export the parts of our library that users will need
-pub use client::SpotClient;
-pub use error::{Result, ScraperError};
+pub use auth::{CredentialProvider, Credentials};
+pub use client::{SpotClient, SpotClientBuilder};
+pub use crawl::CrawlEvent;
+pub use dates::{WIB, WIT, WITA, parse_datetime};
+pub use debug::{DirectoryDump, FailedPage, PageDump};
+pub use diff::{ChangeEvent, TaskRef, diff, diff_json};
+pub use embed::{Embed, EmbedKind, classify_spot_url, classify_url};
+pub use error::{
+    Language, LoginFailure, PageElement, PageKind, ParseProblem, Result, ScraperError,
+};
+pub use ical::CalendarExporter;
 pub use models::*; // Exposes all structs like User, Course, etc.
+pub use render::{to_markdown, to_plain_text};
+pub use retry::RetryPolicy;
+#[cfg(feature = "storage")]
+pub use storage::{RecordKind, Revision, SeenRecord, Storage, SyncReport};
+pub use tasks::TaskFilter;
+pub use throttle::RateLimit;
+pub use watch::{PollReport, Watcher, WatcherBuilder, Webhook, WebhookFormat};
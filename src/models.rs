@@ -1,5 +1,8 @@
-use chrono::NaiveDateTime;
-use serde::{Deserialize, Serialize};
+use crate::embed::Embed;
+use crate::render;
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct User {
@@ -28,7 +31,7 @@ pub struct Rps {
 pub struct TopicInfo {
     pub id: Option<u64>,
     pub course_id: Option<u64>,
-    pub access_time: Option<NaiveDateTime>,
+    pub access_time: Option<DateTime<FixedOffset>>,
     pub is_accessible: bool,
     pub href: Option<String>,
 }
@@ -42,19 +45,163 @@ pub struct DetailCourse {
     pub topics: Vec<TopicInfo>,
 }
 
+/// The contents of a course's RPS (Rencana Pembelajaran Semester) page.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RpsDetail {
+    #[serde(flatten)]
+    pub rps: Rps,
+    /// Program (CPL) and course (CPMK) learning outcomes, in page order.
+    pub learning_outcomes: Vec<LearningOutcome>,
+    pub weekly_plan: Vec<RpsWeek>,
+    pub assessments: Vec<AssessmentWeight>,
+    pub references: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LearningOutcome {
+    /// The outcome's code, such as `CPMK-1`, when the syllabus gives one.
+    pub code: Option<String>,
+    pub description: String,
+}
+
+/// One row of the weekly plan, which may span several weeks.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RpsWeek {
+    /// The weeks the row covers, e.g. `[2, 3]` for "2-3".
+    pub weeks: Vec<u32>,
+    /// The expected ability (Sub-CPMK) for the weeks.
+    pub outcome: String,
+    pub material: String,
+    pub method: String,
+    pub assessment: String,
+    /// The row's share of the final grade, in percent.
+    pub weight: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AssessmentWeight {
+    pub component: String,
+    /// Share of the final grade, in percent.
+    pub weight: f32,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Content {
     pub id: u32,
     pub youtube_id: Option<String>,
     pub raw_html: String,
+    /// The material parsed into typed blocks, with absolute URLs.
+    pub blocks: Vec<ContentBlock>,
+    /// Videos, documents, meetings and files the material links to or embeds.
+    pub embeds: Vec<Embed>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+impl Content {
+    pub fn to_markdown(&self) -> String {
+        render::to_markdown(&self.blocks)
+    }
+
+    pub fn to_plain_text(&self) -> String {
+        render::to_plain_text(&self.blocks)
+    }
+}
+
+/// A block-level element of a topic material.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlock {
+    Paragraph {
+        inlines: Vec<Inline>,
+    },
+    Heading {
+        level: u8,
+        inlines: Vec<Inline>,
+    },
+    List {
+        ordered: bool,
+        items: Vec<ListItem>,
+    },
+    Image {
+        src: String,
+        alt: Option<String>,
+    },
+    Video {
+        url: String,
+        youtube_id: Option<String>,
+    },
+    /// An embedded frame that is not a video, such as a slide deck.
+    Embed {
+        url: String,
+    },
+    /// A link to a downloadable file, such as SPOT's "Download File Materi" button.
+    File {
+        name: String,
+        url: String,
+    },
+    Code {
+        language: Option<String>,
+        code: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ListItem {
+    pub inlines: Vec<Inline>,
+    /// Nested lists and other blocks inside the item.
+    pub children: Vec<ContentBlock>,
+}
+
+/// A run of text inside a [`ContentBlock`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Inline {
+    Text { text: String },
+    Strong { text: String },
+    Emphasis { text: String },
+    Code { text: String },
+    Link { text: String, url: String },
+    LineBreak,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum TaskStatus {
+    /// Open for submission and not answered yet.
     Pending,
+    /// Answered before the deadline.
     Submitted,
     Graded,
+    /// Not answered, and SPOT did not show a submission window.
     NotSubmitted,
+    /// The submission window has not opened yet.
+    NotYetOpen,
+    /// The deadline passed without an answer.
+    Overdue,
+    /// Answered after the deadline.
+    Late,
+}
+
+impl TaskStatus {
+    /// Derives the status from the submission window and the answer, as of `now`.
+    pub fn derive(
+        start_date: Option<DateTime<FixedOffset>>,
+        due_date: Option<DateTime<FixedOffset>>,
+        answer: Option<&Answer>,
+        now: DateTime<FixedOffset>,
+    ) -> Self {
+        match answer {
+            Some(answer) if answer.is_graded => TaskStatus::Graded,
+            Some(answer) => match (answer.date_submitted, due_date) {
+                (Some(submitted), Some(due)) if submitted > due => TaskStatus::Late,
+                _ => TaskStatus::Submitted,
+            },
+            None => match (start_date, due_date) {
+                (Some(start), _) if now < start => TaskStatus::NotYetOpen,
+                (_, Some(due)) if now > due => TaskStatus::Overdue,
+                (None, None) => TaskStatus::NotSubmitted,
+                _ => TaskStatus::Pending,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -64,11 +211,25 @@ pub struct Answer {
     pub file_href: Option<String>,
     pub is_graded: bool,
     pub lecturer_notes: String,
+    pub lecturer_notes_blocks: Vec<ContentBlock>,
     pub score: f32,
-    pub date_submitted: Option<NaiveDateTime>,
+    pub date_submitted: Option<DateTime<FixedOffset>>,
+    pub delete_href: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+impl Answer {
+    pub fn lecturer_notes_markdown(&self) -> String {
+        render::to_markdown(&self.lecturer_notes_blocks)
+    }
+
+    pub fn lecturer_notes_plain_text(&self) -> String {
+        render::to_plain_text(&self.lecturer_notes_blocks)
+    }
+}
+
+/// A task of a topic. Serializing it also writes its current
+/// [`status`](Task::status), which is ignored when reading it back.
+#[derive(Debug, Deserialize, Clone)]
 pub struct Task {
     pub id: Option<u64>,
     pub course_id: u64,
@@ -76,20 +237,172 @@ pub struct Task {
     pub token: String,
     pub title: String,
     pub description: String,
+    pub description_blocks: Vec<ContentBlock>,
     pub file: Option<String>,
-    pub start_date: Option<NaiveDateTime>,
-    pub due_date: Option<NaiveDateTime>,
-    pub status: TaskStatus,
+    pub start_date: Option<DateTime<FixedOffset>>,
+    pub due_date: Option<DateTime<FixedOffset>>,
     pub answer: Option<Answer>,
+    pub form: Option<TaskForm>,
+}
+
+impl Task {
+    pub fn description_markdown(&self) -> String {
+        render::to_markdown(&self.description_blocks)
+    }
+
+    pub fn description_plain_text(&self) -> String {
+        render::to_plain_text(&self.description_blocks)
+    }
+
+    /// The [`TopicInfo`] of the topic page this task was scraped from, for
+    /// fetching the page again with
+    /// [`SpotClient::get_topic_detail`](crate::SpotClient::get_topic_detail).
+    pub fn topic_info(&self) -> TopicInfo {
+        TopicInfo {
+            id: Some(self.topic_id),
+            course_id: Some(self.course_id),
+            access_time: None,
+            is_accessible: true,
+            href: Some(format!("/mhs/topik/{}/{}", self.course_id, self.topic_id)),
+        }
+    }
+
+    /// The status of this task right now. It is derived on every call rather
+    /// than stored, so it is always up to date with the clock.
+    pub fn status(&self) -> TaskStatus {
+        self.status_at(Utc::now().fixed_offset())
+    }
+
+    /// The status of this task as of `now`; see [`TaskStatus::derive`].
+    pub fn status_at(&self, now: DateTime<FixedOffset>) -> TaskStatus {
+        TaskStatus::derive(self.start_date, self.due_date, self.answer.as_ref(), now)
+    }
+}
+
+impl Serialize for Task {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut task = serializer.serialize_struct("Task", 13)?;
+        task.serialize_field("id", &self.id)?;
+        task.serialize_field("course_id", &self.course_id)?;
+        task.serialize_field("topic_id", &self.topic_id)?;
+        task.serialize_field("token", &self.token)?;
+        task.serialize_field("title", &self.title)?;
+        task.serialize_field("description", &self.description)?;
+        task.serialize_field("description_blocks", &self.description_blocks)?;
+        task.serialize_field("file", &self.file)?;
+        task.serialize_field("start_date", &self.start_date)?;
+        task.serialize_field("due_date", &self.due_date)?;
+        task.serialize_field("status", &self.status())?;
+        task.serialize_field("answer", &self.answer)?;
+        task.serialize_field("form", &self.form)?;
+        task.end()
+    }
+}
+
+/// A [`Task`] together with the course and topic it belongs to.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CourseTask {
+    pub course: Course,
+    pub topic: TopicInfo,
+    pub task: Task,
+}
+
+/// The whole course tree at one point in time, as compared by [`diff`](crate::diff()).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Snapshot {
+    pub taken_at: DateTime<FixedOffset>,
+    pub courses: Vec<CourseSnapshot>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CourseSnapshot {
+    pub detail: DetailCourse,
+    /// Details of the topics that were accessible when the snapshot was taken.
+    pub topics: Vec<TopicDetail>,
+}
+
+/// The answer upload form found in a task's modal dialog.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskForm {
+    pub action: String,
+    pub text_field: Option<String>,
+    pub file_field: Option<String>,
+}
+
+/// An answer to be submitted for a [`Task`].
+#[derive(Debug, Clone, Default)]
+pub struct AnswerSubmission {
+    pub content: String,
+    pub file: Option<AnswerFile>,
+}
+
+impl AnswerSubmission {
+    pub fn text(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            file: None,
+        }
+    }
+
+    pub fn with_file(mut self, file: AnswerFile) -> Self {
+        self.file = Some(file);
+        self
+    }
+}
+
+/// A file attached to an [`AnswerSubmission`].
+#[derive(Debug, Clone)]
+pub struct AnswerFile {
+    pub file_name: String,
+    pub bytes: Vec<u8>,
+    pub mime_type: Option<String>,
+}
+
+impl AnswerFile {
+    pub fn new(file_name: impl Into<String>, bytes: Vec<u8>) -> Self {
+        Self {
+            file_name: file_name.into(),
+            bytes,
+            mime_type: None,
+        }
+    }
+
+    pub fn mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    /// Reads a file from disk, using its file name as the upload name.
+    pub async fn from_path(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let bytes = tokio::fs::read(path).await?;
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "upload".to_string());
+        Ok(Self::new(file_name, bytes))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TopicDetail {
     pub id: u64,
-    pub access_time: Option<NaiveDateTime>,
+    pub access_time: Option<DateTime<FixedOffset>>,
     pub is_accessible: bool,
     pub href: String,
     pub description: Option<String>,
     pub contents: Vec<Content>,
     pub tasks: Vec<Task>,
 }
+
+/// Metadata of a file fetched with [`SpotClient::download`](crate::SpotClient::download).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Download {
+    pub url: String,
+    pub content_type: Option<String>,
+    pub file_name: Option<String>,
+    /// The `Content-Length` announced by the server, if any.
+    pub content_length: Option<u64>,
+    /// The number of bytes actually written.
+    pub size: u64,
+}
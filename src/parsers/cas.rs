@@ -0,0 +1,69 @@
+// src/parsers/cas.rs
+
+use super::content::normalize_whitespace;
+use super::selectors;
+use crate::error::LoginFailure;
+use reqwest::{StatusCode, Url};
+use scraper::Html;
+
+const MAINTENANCE_WORDS: &[&str] = &["maintenance", "pemeliharaan", "perbaikan"];
+const LOCKED_WORDS: &[&str] = &[
+    "locked",
+    "terkunci",
+    "blocked",
+    "diblokir",
+    "disabled",
+    "dinonaktifkan",
+    "tidak aktif",
+    "too many",
+    "terlalu banyak",
+];
+
+/// Works out why a login did not end up on SPOT, from the page CAS answered
+/// with. Returns the reason and the error message CAS showed, if any.
+pub fn diagnose_login_failure(
+    html: &str,
+    status: StatusCode,
+    final_url: &Url,
+    cas_url: &Url,
+) -> (LoginFailure, Option<String>) {
+    if final_url.origin() != cas_url.origin() {
+        return (LoginFailure::UnexpectedRedirect, None);
+    }
+
+    let document = Html::parse_document(html);
+    let s = selectors::get();
+    let message = document
+        .select(&s.cas_error)
+        .map(|el| normalize_whitespace(&el.text().collect::<String>()))
+        .find(|text| !text.is_empty());
+
+    // Maintenance pages rarely have an error box, so look at the headings too.
+    let headline = document
+        .select(&s.title)
+        .chain(document.select(&s.page_heading))
+        .map(|el| el.text().collect::<String>())
+        .chain(message.clone())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    let lower_message = message.as_deref().unwrap_or_default().to_lowercase();
+    let mentions = |text: &str, words: &[&str]| words.iter().any(|word| text.contains(word));
+
+    let reason = if status == StatusCode::SERVICE_UNAVAILABLE
+        || mentions(&headline, MAINTENANCE_WORDS)
+    {
+        LoginFailure::Maintenance
+    } else if document.select(&s.cas_captcha).next().is_some() || lower_message.contains("captcha")
+    {
+        LoginFailure::CaptchaRequired
+    } else if mentions(&lower_message, LOCKED_WORDS) {
+        LoginFailure::AccountLocked
+    } else if message.is_some() || status == StatusCode::UNAUTHORIZED {
+        // Any other message on the login form is about the NIM or password.
+        LoginFailure::InvalidCredentials
+    } else {
+        LoginFailure::Unknown
+    };
+    (reason, message)
+}
@@ -0,0 +1,462 @@
+// src/parsers/content.rs
+
+use crate::embed::{Embed, EmbedKind, classify_spot_url};
+use crate::models::{ContentBlock, Inline, ListItem};
+use ego_tree::NodeRef;
+use reqwest::Url;
+use scraper::{ElementRef, Node};
+
+/// Parses the children of a material container into typed blocks.
+pub fn parse_content_blocks(element: ElementRef, base_url: &Url) -> Vec<ContentBlock> {
+    let mut parser = BlockParser {
+        base_url,
+        blocks: Vec::new(),
+        inlines: Vec::new(),
+    };
+    parser.walk(*element);
+    parser.flush();
+    parser.blocks
+}
+
+struct BlockParser<'a> {
+    base_url: &'a Url,
+    blocks: Vec<ContentBlock>,
+    /// Inline content waiting to be closed into a paragraph.
+    inlines: Vec<Inline>,
+}
+
+impl BlockParser<'_> {
+    fn walk(&mut self, node: NodeRef<Node>) {
+        for child in node.children() {
+            match child.value() {
+                Node::Text(text) => push_text(&mut self.inlines, text),
+                Node::Element(_) => {
+                    if let Some(element) = ElementRef::wrap(child) {
+                        self.element(element);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn element(&mut self, element: ElementRef) {
+        let name = element.value().name();
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.flush();
+                let inlines = self.collect_inlines(element);
+                if !inlines.is_empty() {
+                    self.blocks.push(ContentBlock::Heading {
+                        level: name[1..].parse().unwrap_or(1),
+                        inlines,
+                    });
+                }
+            }
+            "ul" | "ol" => {
+                self.flush();
+                let list = self.list(element);
+                self.blocks.push(list);
+            }
+            "pre" => {
+                self.flush();
+                let code_el = element
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .find(|c| c.value().name() == "code")
+                    .unwrap_or(element);
+                let language = code_el
+                    .value()
+                    .classes()
+                    .chain(element.value().classes())
+                    .find_map(|c| c.strip_prefix("language-").or(c.strip_prefix("lang-")))
+                    .map(String::from);
+                self.blocks.push(ContentBlock::Code {
+                    language,
+                    code: code_el
+                        .text()
+                        .collect::<String>()
+                        .trim_matches('\n')
+                        .to_string(),
+                });
+            }
+            "img" => {
+                if let Some(src) = self.attr_url(element, "src") {
+                    self.flush();
+                    self.blocks.push(ContentBlock::Image {
+                        src,
+                        alt: element
+                            .value()
+                            .attr("alt")
+                            .map(str::trim)
+                            .filter(|a| !a.is_empty())
+                            .map(String::from),
+                    });
+                }
+            }
+            "iframe" | "embed" => {
+                if let Some(url) = self.attr_url(element, "src") {
+                    self.flush();
+                    self.blocks
+                        .push(media_block(classify(&url, self.base_url), url));
+                }
+            }
+            "video" => {
+                let url = self.attr_url(element, "src").or_else(|| {
+                    element
+                        .children()
+                        .filter_map(ElementRef::wrap)
+                        .filter(|c| c.value().name() == "source")
+                        .find_map(|c| self.attr_url(c, "src"))
+                });
+                if let Some(url) = url {
+                    self.flush();
+                    self.blocks.push(ContentBlock::Video {
+                        url,
+                        youtube_id: None,
+                    });
+                }
+            }
+            "a" => match self.attr_url(element, "href") {
+                // A file link inside running text stays a link, so the
+                // sentence is not split; embeds still pick it up.
+                Some(url) if is_file_url(&url, self.base_url) && self.stands_alone(element) => {
+                    self.flush();
+                    let name = normalize_whitespace(&element.text().collect::<String>());
+                    self.blocks.push(ContentBlock::File {
+                        name: if name.is_empty() {
+                            file_name_from_url(&url)
+                        } else {
+                            name
+                        },
+                        url,
+                    });
+                }
+                _ => self.inline(element),
+            },
+            "p" | "div" | "section" | "article" | "blockquote" | "center" | "figure" | "table"
+            | "tbody" | "thead" | "tr" | "td" | "th" | "header" | "footer" => {
+                self.flush();
+                self.walk(*element);
+                self.flush();
+            }
+            "script" | "style" | "noscript" | "template" => {}
+            _ => self.inline(element),
+        }
+    }
+
+    /// Whether `element` is the only content of the paragraph or list item it
+    /// is in: nothing but whitespace before it, or after it up to the next block.
+    fn stands_alone(&self, element: ElementRef) -> bool {
+        let nothing_before = self.inlines.iter().all(|inline| match inline {
+            Inline::Text { text } => text.trim().is_empty(),
+            Inline::LineBreak => true,
+            _ => false,
+        });
+        nothing_before
+            && element
+                .next_siblings()
+                .take_while(|sibling| {
+                    !ElementRef::wrap(*sibling).is_some_and(|e| is_block(e.value().name()))
+                })
+                .all(|sibling| match sibling.value() {
+                    Node::Text(text) => text.trim().is_empty(),
+                    Node::Element(e) => e.name() == "br",
+                    _ => true,
+                })
+    }
+
+    /// Handles an inline element, descending into it when it wraps block content.
+    fn inline(&mut self, element: ElementRef) {
+        if contains_block(element) {
+            self.walk(*element);
+        } else {
+            let mut inlines = std::mem::take(&mut self.inlines);
+            self.collect_element(element, &mut inlines);
+            self.inlines = inlines;
+        }
+    }
+
+    fn list(&self, element: ElementRef) -> ContentBlock {
+        let items = element
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|c| c.value().name() == "li")
+            .map(|li| {
+                let mut item = BlockParser {
+                    base_url: self.base_url,
+                    blocks: Vec::new(),
+                    inlines: Vec::new(),
+                };
+                item.walk(*li);
+                item.flush();
+                // The leading paragraph is the item text; anything after it is nested.
+                let mut children = item.blocks;
+                let inlines = match children.first_mut() {
+                    Some(ContentBlock::Paragraph { inlines }) => {
+                        let inlines = std::mem::take(inlines);
+                        children.remove(0);
+                        inlines
+                    }
+                    _ => Vec::new(),
+                };
+                ListItem { inlines, children }
+            })
+            .collect();
+        ContentBlock::List {
+            ordered: element.value().name() == "ol",
+            items,
+        }
+    }
+
+    /// Collects the inline content of `element`, flattening nested markup.
+    fn collect_inlines(&self, element: ElementRef) -> Vec<Inline> {
+        let mut inlines = Vec::new();
+        self.collect_into(*element, &mut inlines);
+        trim_inlines(inlines)
+    }
+
+    fn collect_into(&self, node: NodeRef<Node>, inlines: &mut Vec<Inline>) {
+        for child in node.children() {
+            match child.value() {
+                Node::Text(text) => push_text(inlines, text),
+                Node::Element(_) => {
+                    if let Some(element) = ElementRef::wrap(child) {
+                        self.collect_element(element, inlines);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn collect_element(&self, element: ElementRef, inlines: &mut Vec<Inline>) {
+        let text = || normalize_whitespace(&element.text().collect::<String>());
+        match element.value().name() {
+            "br" => inlines.push(Inline::LineBreak),
+            "strong" | "b" if !text().is_empty() => inlines.push(Inline::Strong { text: text() }),
+            "em" | "i" if !text().is_empty() => inlines.push(Inline::Emphasis { text: text() }),
+            "code" | "kbd" | "samp" => inlines.push(Inline::Code {
+                text: element.text().collect(),
+            }),
+            "a" => match self.attr_url(element, "href") {
+                Some(url) => {
+                    let text = text();
+                    inlines.push(Inline::Link {
+                        text: if text.is_empty() { url.clone() } else { text },
+                        url,
+                    });
+                }
+                None => self.collect_into(*element, inlines),
+            },
+            "script" | "style" | "img" | "iframe" => {}
+            _ => self.collect_into(*element, inlines),
+        }
+    }
+
+    /// Closes the pending inline content into a paragraph.
+    fn flush(&mut self) {
+        let inlines = trim_inlines(std::mem::take(&mut self.inlines));
+        if !inlines.is_empty() {
+            self.blocks.push(ContentBlock::Paragraph { inlines });
+        }
+    }
+
+    fn attr_url(&self, element: ElementRef, attr: &str) -> Option<String> {
+        let value = element.value().attr(attr)?.trim();
+        if value.is_empty() || value.starts_with('#') || value.starts_with("javascript:") {
+            return None;
+        }
+        self.base_url.join(value).ok().map(String::from)
+    }
+}
+
+/// Classifies a link found on a page served from `base_url`.
+fn classify(url: &str, base_url: &Url) -> Option<EmbedKind> {
+    classify_spot_url(url, base_url.host_str().unwrap_or_default())
+}
+
+fn media_block(kind: Option<EmbedKind>, url: String) -> ContentBlock {
+    match kind {
+        Some(EmbedKind::YouTube { video_id }) => ContentBlock::Video {
+            url,
+            youtube_id: Some(video_id),
+        },
+        Some(EmbedKind::Vimeo { .. }) => ContentBlock::Video {
+            url,
+            youtube_id: None,
+        },
+        _ => ContentBlock::Embed { url },
+    }
+}
+
+fn is_file_url(url: &str, base_url: &Url) -> bool {
+    matches!(classify(url, base_url), Some(EmbedKind::DirectFile { .. }))
+}
+
+/// Collects every frame, video and link in a material that points to a known service.
+pub fn collect_embeds(element: ElementRef, base_url: &Url) -> Vec<Embed> {
+    let mut embeds: Vec<Embed> = Vec::new();
+    for el in element.descendants().filter_map(ElementRef::wrap) {
+        let attr = match el.value().name() {
+            "iframe" | "embed" | "video" | "source" => "src",
+            "a" => "href",
+            _ => continue,
+        };
+        let Some(url) = el
+            .value()
+            .attr(attr)
+            .and_then(|value| base_url.join(value.trim()).ok())
+            .map(String::from)
+        else {
+            continue;
+        };
+        if embeds.iter().any(|e| e.url == url) {
+            continue;
+        }
+        if let Some(kind) = classify(&url, base_url) {
+            embeds.push(Embed { url, kind });
+        }
+    }
+    embeds
+}
+
+fn file_name_from_url(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            u.path_segments()?
+                .next_back()
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    percent_encoding::percent_decode_str(s)
+                        .decode_utf8_lossy()
+                        .into_owned()
+                })
+        })
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Elements that start a new block when parsing, and so end a paragraph.
+fn is_block(name: &str) -> bool {
+    matches!(
+        name,
+        "p" | "div"
+            | "section"
+            | "article"
+            | "blockquote"
+            | "center"
+            | "figure"
+            | "table"
+            | "tr"
+            | "td"
+            | "th"
+            | "header"
+            | "footer"
+            | "ul"
+            | "ol"
+            | "li"
+            | "pre"
+            | "img"
+            | "iframe"
+            | "embed"
+            | "video"
+            | "h1"
+            | "h2"
+            | "h3"
+            | "h4"
+            | "h5"
+            | "h6"
+    )
+}
+
+fn contains_block(element: ElementRef) -> bool {
+    element.descendants().filter_map(ElementRef::wrap).any(|e| {
+        matches!(
+            e.value().name(),
+            "p" | "div"
+                | "ul"
+                | "ol"
+                | "pre"
+                | "table"
+                | "iframe"
+                | "img"
+                | "video"
+                | "embed"
+                | "h1"
+                | "h2"
+                | "h3"
+                | "h4"
+                | "h5"
+                | "h6"
+                | "blockquote"
+        )
+    })
+}
+
+/// Appends text, collapsing whitespace and merging with a preceding text run.
+fn push_text(inlines: &mut Vec<Inline>, text: &str) {
+    let mut collapsed = normalize_whitespace(text);
+    if collapsed.is_empty() {
+        if text.is_empty() {
+            return;
+        }
+        collapsed = " ".to_string();
+    } else {
+        if text.starts_with(char::is_whitespace) {
+            collapsed.insert(0, ' ');
+        }
+        if text.ends_with(char::is_whitespace) {
+            collapsed.push(' ');
+        }
+    }
+    match inlines.last_mut() {
+        Some(Inline::Text { text }) => {
+            if text.ends_with(' ') && collapsed.starts_with(' ') {
+                text.push_str(&collapsed[1..]);
+            } else {
+                text.push_str(&collapsed);
+            }
+        }
+        _ => inlines.push(Inline::Text { text: collapsed }),
+    }
+}
+
+/// Removes leading and trailing whitespace and line breaks from a run of inlines.
+fn trim_inlines(mut inlines: Vec<Inline>) -> Vec<Inline> {
+    while let Some(first) = inlines.first_mut() {
+        match first {
+            Inline::Text { text } if text.trim().is_empty() => {
+                inlines.remove(0);
+            }
+            Inline::LineBreak => {
+                inlines.remove(0);
+            }
+            Inline::Text { text } => {
+                *text = text.trim_start().to_string();
+                break;
+            }
+            _ => break,
+        }
+    }
+    while let Some(last) = inlines.last_mut() {
+        match last {
+            Inline::Text { text } if text.trim().is_empty() => {
+                inlines.pop();
+            }
+            Inline::LineBreak => {
+                inlines.pop();
+            }
+            Inline::Text { text } => {
+                *text = text.trim_end().to_string();
+                break;
+            }
+            _ => break,
+        }
+    }
+    inlines
+}
+
+pub(crate) fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
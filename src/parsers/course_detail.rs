@@ -1,28 +1,22 @@
 // src/parsers/course_detail.rs
 
+use super::selectors;
+use crate::dates::parse_datetime;
 use crate::error::Result;
 use crate::models::{Course, DetailCourse, Rps, TopicInfo};
-use chrono::NaiveDateTime;
-use scraper::{ElementRef, Html, Selector};
-
-fn parse_dt(s: &str) -> Option<NaiveDateTime> {
-    let t = s.trim();
-    [
-        "%d-%m-%Y %H:%M",
-        "%d-%m-%Y %H:%M:%S",
-        "%d/%m/%Y %H:%M",
-        "%d/%m/%Y %H:%M:%S",
-    ]
-    .iter()
-    .find_map(|fmt| NaiveDateTime::parse_from_str(t, fmt).ok())
-}
-
-pub fn parse_course_detail_from_html(html: &str, course: Course) -> Result<DetailCourse> {
+use chrono::FixedOffset;
+use scraper::{ElementRef, Html};
+
+pub fn parse_course_detail_from_html(
+    html: &str,
+    course: Course,
+    zone: FixedOffset,
+) -> Result<DetailCourse> {
     let document = Html::parse_document(html);
+    let s = selectors::get();
 
     // Handle cases where the course is not yet configured by the lecturer
-    let warning_selector = Selector::parse(".white-box.bg-warning").unwrap();
-    if document.select(&warning_selector).next().is_some() {
+    if document.select(&s.course_unset).next().is_some() {
         return Ok(DetailCourse {
             course_info: course,
             description: "Course has not been set by the lecturer.".to_string(),
@@ -35,35 +29,32 @@ pub fn parse_course_detail_from_html(html: &str, course: Course) -> Result<Detai
     }
 
     // --- Robust Logic for Description and RPS ---
-    let rps_link_selector = Selector::parse("a.btn-danger[href*='/mhs/rps/']").unwrap();
-    let rps_anchor = document.select(&rps_link_selector).next();
+    let rps_anchor = document.select(&s.rps_link).next();
 
     let (rps, description) = if let Some(anchor) = rps_anchor {
         let href = anchor.value().attr("href").map(String::from);
         let rps_data = Rps {
             id: href
                 .as_deref()
-                .and_then(|h| h.split('/').last())
+                .and_then(|h| h.split('/').next_back())
                 .and_then(|v| v.parse::<u64>().ok()),
             href,
         };
 
-        let white_box_selector = Selector::parse(".white-box").unwrap();
         let description_container = anchor.ancestors().find_map(|ancestor_node| {
-            if let Some(element_ref) = ElementRef::wrap(ancestor_node) {
-                if white_box_selector.matches(&element_ref) {
-                    return Some(element_ref);
-                }
+            if let Some(element_ref) = ElementRef::wrap(ancestor_node)
+                && s.white_box.matches(&element_ref)
+            {
+                return Some(element_ref);
             }
             None
         });
 
-        let p_selector = Selector::parse("p").unwrap();
         let desc = description_container
             .and_then(|container| {
                 container
-                    .select(&p_selector)
-                    .find(|p| p.select(&rps_link_selector).next().is_none())
+                    .select(&s.p)
+                    .find(|p| p.select(&s.rps_link).next().is_none())
             })
             .map(|p| p.text().collect::<String>().trim().to_string())
             .unwrap_or_default();
@@ -80,12 +71,10 @@ pub fn parse_course_detail_from_html(html: &str, course: Course) -> Result<Detai
     };
 
     // --- Topic Parsing Logic ---
-    let topic_selector = Selector::parse(".container-fluid .block4").unwrap();
     let topics: Vec<TopicInfo> = document
-        .select(&topic_selector)
+        .select(&s.topic_block)
         .map(|topic_el| {
-            let link_selector = Selector::parse(".panel-body a.btn-info").unwrap();
-            let link_element = topic_el.select(&link_selector).next();
+            let link_element = topic_el.select(&s.topic_link).next();
 
             let is_accessible = link_element.is_some();
 
@@ -107,20 +96,19 @@ pub fn parse_course_detail_from_html(html: &str, course: Course) -> Result<Detai
                 }
             });
 
-            let time_selector = Selector::parse(".panel-body div div button.disabled").unwrap();
-
             TopicInfo {
                 id: relative_path
                     .as_deref()
-                    .and_then(|path| path.split('/').last())
+                    .and_then(|path| path.split('/').next_back())
                     .and_then(|v| v.parse::<u64>().ok()),
                 course_id: relative_path
                     .as_deref()
                     .and_then(|path| path.split('/').nth(3))
                     .and_then(|v| v.parse::<u64>().ok()),
-                access_time: topic_el.select(&time_selector).next().and_then(|btn| {
-                    parse_dt(&btn.text().collect::<String>().replace("Waktu Akses:", ""))
-                }),
+                access_time: topic_el
+                    .select(&s.topic_access_button)
+                    .next()
+                    .and_then(|btn| parse_datetime(&btn.text().collect::<String>(), zone)),
                 is_accessible,
                 href: relative_path,
             }
@@ -1,30 +1,51 @@
-use crate::error::{Result, ScraperError};
+use super::selectors::{self, CSS};
+use super::{body_excerpt, excerpt};
+use crate::error::{PageElement, PageKind, ParseProblem, Result, ScraperError};
 use crate::models::Course;
-use scraper::{ElementRef, Html, Selector};
+use scraper::{ElementRef, Html};
 
 fn extract_course_from_row(row: ElementRef) -> Result<Course> {
-    let cell_selector = Selector::parse("td").unwrap();
-    let cells: Vec<_> = row.select(&cell_selector).collect();
+    let s = selectors::get();
+    let cells: Vec<_> = row.select(&s.td).collect();
 
     if cells.len() < 5 {
-        return Err(ScraperError::ParsingError(
-            "Baris tabel mata kuliah tidak memiliki kolom yang cukup".to_string(),
-        ));
+        return Err(ScraperError::ParsingError {
+            page: PageKind::Dashboard,
+            problem: ParseProblem::TooFewColumns,
+            selector: Some(CSS.td),
+            url: None,
+            excerpt: Some(excerpt(row)),
+        });
     }
 
     let href = cells[1]
-        .select(&Selector::parse("a").unwrap())
+        .select(&s.a)
         .next()
         .and_then(|a| a.value().attr("href"))
-        .ok_or_else(|| ScraperError::ElementNotFound("Link mata kuliah (href)".to_string()))?;
+        .ok_or_else(|| ScraperError::ElementNotFound {
+            page: PageKind::Dashboard,
+            element: PageElement::CourseLink,
+            selector: CSS.a,
+            url: None,
+            excerpt: Some(excerpt(cells[1])),
+        })?;
 
-    let id = href.split('/').last().and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
+    let id = href
+        .split('/')
+        .next_back()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(0);
 
     let course = Course {
         id,
         code: cells[0].text().collect::<String>().trim().to_string(),
         name: cells[1].text().collect::<String>().trim().to_string(),
-        credits: cells[2].text().collect::<String>().trim().parse().unwrap_or(0),
+        credits: cells[2]
+            .text()
+            .collect::<String>()
+            .trim()
+            .parse()
+            .unwrap_or(0),
         lecturer: cells[3].text().collect::<String>().trim().to_string(),
         academic_year: cells[4].text().collect::<String>().trim().to_string(),
         href: href.to_string(),
@@ -35,19 +56,29 @@ fn extract_course_from_row(row: ElementRef) -> Result<Course> {
 
 pub fn parse_courses_from_html(html: &str) -> Result<Vec<Course>> {
     let document = Html::parse_document(html);
-    let row_selector = Selector::parse("table > tbody > tr").unwrap();
-
     let mut courses = Vec::new();
-    for row in document.select(&row_selector) {
-        if let Ok(course) = extract_course_from_row(row) {
-            courses.push(course);
+    let mut row_error = None;
+    for row in document.select(&selectors::get().course_row) {
+        match extract_course_from_row(row) {
+            Ok(course) => courses.push(course),
+            Err(e) => {
+                row_error.get_or_insert(e);
+            }
         }
     }
 
     if courses.is_empty() {
-        return Err(ScraperError::ElementNotFound(
-            "Tidak ada baris mata kuliah yang ditemukan di dalam tabel".to_string(),
-        ));
+        // A row that did not parse says more than "no rows".
+        if let Some(e) = row_error {
+            return Err(e);
+        }
+        return Err(ScraperError::ElementNotFound {
+            page: PageKind::Dashboard,
+            element: PageElement::CourseRows,
+            selector: CSS.course_row,
+            url: None,
+            excerpt: Some(body_excerpt(&document)),
+        });
     }
 
     Ok(courses)
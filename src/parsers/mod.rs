@@ -1,4 +1,32 @@
+pub mod cas;
+pub mod content;
 pub mod course_detail;
 pub mod courses;
+pub mod rps;
+pub mod selectors;
 pub mod topic_detail;
 pub mod user;
+
+use scraper::{ElementRef, Html};
+
+/// How much HTML a parsing error keeps.
+const EXCERPT_CHARS: usize = 400;
+
+/// The start of an element's HTML with whitespace collapsed, for error context.
+pub(crate) fn excerpt(element: ElementRef) -> String {
+    let html = content::normalize_whitespace(&element.html());
+    match html.char_indices().nth(EXCERPT_CHARS) {
+        Some((end, _)) => format!("{}…", &html[..end]),
+        None => html,
+    }
+}
+
+/// Like [`excerpt`], for the `<body>` of a page.
+pub(crate) fn body_excerpt(document: &Html) -> String {
+    excerpt(
+        document
+            .select(&selectors::get().body)
+            .next()
+            .unwrap_or_else(|| document.root_element()),
+    )
+}
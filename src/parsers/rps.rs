@@ -0,0 +1,306 @@
+// src/parsers/rps.rs
+
+use super::body_excerpt;
+use super::content::normalize_whitespace;
+use super::selectors::{self, CSS};
+use crate::error::{PageElement, PageKind, Result, ScraperError};
+use crate::models::{AssessmentWeight, LearningOutcome, Rps, RpsDetail, RpsWeek};
+use scraper::{ElementRef, Html, Node};
+
+/// The last week number accepted from the weekly plan. Anything later is a
+/// typo, and expanding a range like "1-40000000" would take a lot of memory.
+const MAX_WEEK: u32 = 52;
+/// The widest `colspan` honoured, well above the columns of any RPS table.
+const MAX_COLSPAN: usize = 32;
+
+/// Columns of the weekly plan table, matched by keywords in their headers.
+#[derive(Clone, Copy, PartialEq)]
+enum Column {
+    Week,
+    Outcome,
+    Material,
+    Method,
+    Assessment,
+    Weight,
+}
+
+pub fn parse_rps_from_html(html: &str, rps: Rps) -> Result<RpsDetail> {
+    let document = Html::parse_document(html);
+    let s = selectors::get();
+
+    let mut detail = RpsDetail {
+        rps,
+        learning_outcomes: Vec::new(),
+        weekly_plan: Vec::new(),
+        assessments: Vec::new(),
+        references: Vec::new(),
+    };
+
+    if let Some(table) = document
+        .select(&s.table)
+        .find(|table| weekly_plan_columns(*table).is_some())
+    {
+        detail.weekly_plan = parse_weekly_plan(table);
+    }
+
+    for heading in document.select(&s.heading) {
+        let title = heading.text().collect::<String>().to_lowercase();
+        let Some(section) = section_content(heading) else {
+            continue;
+        };
+
+        if title.contains("capaian") || title.contains("learning outcome") {
+            detail.learning_outcomes = parse_learning_outcomes(section);
+        } else if title.contains("pustaka")
+            || title.contains("referensi")
+            || title.contains("rujukan")
+        {
+            detail.references = list_texts(section);
+        } else if (title.contains("penilaian") || title.contains("evaluasi"))
+            && weekly_plan_columns(section).is_none()
+        {
+            detail.assessments = parse_assessments(section);
+        }
+    }
+
+    if detail.learning_outcomes.is_empty()
+        && detail.weekly_plan.is_empty()
+        && detail.assessments.is_empty()
+        && detail.references.is_empty()
+    {
+        return Err(ScraperError::ElementNotFound {
+            page: PageKind::Rps,
+            element: PageElement::RpsContent,
+            selector: CSS.heading,
+            url: None,
+            excerpt: Some(body_excerpt(&document)),
+        });
+    }
+    Ok(detail)
+}
+
+/// Finds the first table or list after a heading, stopping at the next heading.
+fn section_content(heading: ElementRef) -> Option<ElementRef> {
+    let content_selector = &selectors::get().rps_section_content;
+    for sibling in heading.next_siblings().filter_map(ElementRef::wrap) {
+        let name = sibling.value().name();
+        if matches!(name, "h1" | "h2" | "h3" | "h4" | "h5" | "h6") {
+            return None;
+        }
+        if matches!(name, "table" | "ol" | "ul") {
+            return Some(sibling);
+        }
+        if let Some(nested) = sibling.select(content_selector).next() {
+            return Some(nested);
+        }
+    }
+    None
+}
+
+/// Maps each column of a weekly plan table, or `None` if the table has no week column.
+fn weekly_plan_columns(table: ElementRef) -> Option<Vec<Option<Column>>> {
+    let s = selectors::get();
+    table.select(&s.tr).find_map(|row| {
+        let columns: Vec<Option<Column>> = row
+            .select(&s.th)
+            .flat_map(|th| {
+                let header = th.text().collect::<String>().to_lowercase();
+                let column = if header.contains("minggu") {
+                    Some(Column::Week)
+                } else if header.contains("bobot") {
+                    Some(Column::Weight)
+                } else if header.contains("kemampuan") || header.contains("sub-cpmk") {
+                    Some(Column::Outcome)
+                } else if header.contains("materi") || header.contains("bahan kajian") {
+                    Some(Column::Material)
+                } else if header.contains("metode") || header.contains("bentuk") {
+                    Some(Column::Method)
+                } else if header.contains("penilaian") || header.contains("indikator") {
+                    Some(Column::Assessment)
+                } else {
+                    None
+                };
+                std::iter::repeat_n(column, colspan(th))
+            })
+            .collect();
+        columns.contains(&Some(Column::Week)).then_some(columns)
+    })
+}
+
+fn parse_weekly_plan(table: ElementRef) -> Vec<RpsWeek> {
+    let Some(columns) = weekly_plan_columns(table) else {
+        return Vec::new();
+    };
+
+    table
+        .select(&selectors::get().tr)
+        .filter_map(|row| {
+            let cells = row_cells(row);
+            let cell = |column: Column| {
+                columns
+                    .iter()
+                    .position(|c| *c == Some(column))
+                    .and_then(|i| cells.get(i))
+                    .cloned()
+                    .unwrap_or_default()
+            };
+
+            let weeks = parse_weeks(&cell(Column::Week));
+            if weeks.is_empty() {
+                return None;
+            }
+            Some(RpsWeek {
+                weeks,
+                outcome: cell(Column::Outcome),
+                material: cell(Column::Material),
+                method: cell(Column::Method),
+                assessment: cell(Column::Assessment),
+                weight: parse_percent(&cell(Column::Weight)),
+            })
+        })
+        .collect()
+}
+
+fn parse_learning_outcomes(section: ElementRef) -> Vec<LearningOutcome> {
+    if section.value().name() != "table" {
+        return list_texts(section)
+            .into_iter()
+            .map(|text| match text.split_once(':') {
+                Some((code, description)) if is_outcome_code(code.trim()) => LearningOutcome {
+                    code: Some(code.trim().to_string()),
+                    description: description.trim().to_string(),
+                },
+                _ => LearningOutcome {
+                    code: None,
+                    description: text,
+                },
+            })
+            .collect();
+    }
+
+    section
+        .select(&selectors::get().tr)
+        .filter_map(|row| {
+            let cells = row_cells(row);
+            match cells.as_slice() {
+                [] => None,
+                [description] => Some(LearningOutcome {
+                    code: None,
+                    description: description.clone(),
+                }),
+                [code, description, ..] => Some(LearningOutcome {
+                    code: Some(code.clone()).filter(|c| !c.is_empty()),
+                    description: description.clone(),
+                }),
+            }
+        })
+        .filter(|outcome| !outcome.description.is_empty())
+        .collect()
+}
+
+fn parse_assessments(section: ElementRef) -> Vec<AssessmentWeight> {
+    section
+        .select(&selectors::get().tr)
+        .filter_map(|row| {
+            let cells = row_cells(row);
+            let component = cells.first()?.clone();
+            let lower = component.to_lowercase();
+            if lower.starts_with("total") || lower.starts_with("jumlah") {
+                return None;
+            }
+            let weight = parse_percent(cells.last()?)?;
+            Some(AssessmentWeight { component, weight })
+        })
+        .collect()
+}
+
+/// Returns the text of each item of a list, or of each row of a table.
+fn list_texts(section: ElementRef) -> Vec<String> {
+    let s = selectors::get();
+    if section.value().name() == "table" {
+        return section
+            .select(&s.tr)
+            .filter_map(|row| row_cells(row).pop())
+            .filter(|text| !text.is_empty())
+            .collect();
+    }
+
+    section
+        .select(&s.li)
+        .map(cell_text)
+        .filter(|text| !text.is_empty())
+        .collect()
+}
+
+/// Returns the text of a row's `<td>` cells, repeating spanned cells as empty ones.
+fn row_cells(row: ElementRef) -> Vec<String> {
+    row.select(&selectors::get().td)
+        .flat_map(|td| {
+            std::iter::once(cell_text(td))
+                .chain(std::iter::repeat_n(String::new(), colspan(td) - 1))
+        })
+        .collect()
+}
+
+fn colspan(cell: ElementRef) -> usize {
+    cell.value()
+        .attr("colspan")
+        .and_then(|span| span.trim().parse().ok())
+        .unwrap_or(1)
+        .clamp(1, MAX_COLSPAN)
+}
+
+/// Collects a cell's text, keeping `<br>` line breaks and dropping a leading `:`.
+fn cell_text(cell: ElementRef) -> String {
+    let mut text = String::new();
+    for node in cell.descendants() {
+        match node.value() {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(e) if e.name() == "br" => text.push('\n'),
+            _ => {}
+        }
+    }
+    let text = text
+        .lines()
+        .map(normalize_whitespace)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.trim_start_matches(':').trim_start().to_string()
+}
+
+/// Parses week numbers such as "8", "2-3" or "9, 10". Weeks after
+/// [`MAX_WEEK`], and ranges reaching past it, are dropped.
+fn parse_weeks(text: &str) -> Vec<u32> {
+    text.split([',', '&'])
+        .flat_map(|part| {
+            let mut bounds = part.split(['-', '–']).map(|n| n.trim().parse::<u32>().ok());
+            match (bounds.next().flatten(), bounds.next().flatten()) {
+                (Some(start), Some(end)) if start <= end && end <= MAX_WEEK => {
+                    (start..=end).collect()
+                }
+                (Some(week), None) if week <= MAX_WEEK => vec![week],
+                _ => Vec::new(),
+            }
+        })
+        .collect()
+}
+
+/// Parses a percentage such as "30", "15%" or "10,5 %".
+fn parse_percent(text: &str) -> Option<f32> {
+    text.trim()
+        .trim_end_matches('%')
+        .trim()
+        .replace(',', ".")
+        .parse()
+        .ok()
+}
+
+/// Whether `text` looks like an outcome code such as `CPL-3` or `CPMK 1`.
+fn is_outcome_code(text: &str) -> bool {
+    let upper = text.to_uppercase();
+    ["CPL", "CPMK", "SUB-CPMK", "LO", "CLO"]
+        .iter()
+        .any(|prefix| upper.starts_with(prefix))
+        && text.len() <= 16
+}
@@ -0,0 +1,112 @@
+// src/parsers/selectors.rs
+
+//! Every CSS selector the parsers use, compiled once on first use.
+
+use crate::error::{Result, ScraperError};
+use scraper::Selector;
+use std::sync::LazyLock;
+
+static SELECTORS: LazyLock<std::result::Result<Selectors, String>> =
+    LazyLock::new(Selectors::compile);
+
+/// Declares the [`Selectors`] table, one field per selector.
+macro_rules! selectors {
+    ($($(#[$doc:meta])* $name:ident => $css:literal,)*) => {
+        pub struct Selectors {
+            $($(#[$doc])* pub $name: Selector,)*
+        }
+
+        /// The source of each selector, for error messages.
+        // Only the selectors that errors name are read.
+        #[allow(dead_code)]
+        pub struct Css {
+            $(pub $name: &'static str,)*
+        }
+
+        pub const CSS: Css = Css {
+            $($name: $css,)*
+        };
+
+        impl Selectors {
+            fn compile() -> std::result::Result<Self, String> {
+                Ok(Self {
+                    $($name: Selector::parse($css)
+                        .map_err(|e| format!("invalid selector {:?}: {}", $css, e))?,)*
+                })
+            }
+        }
+    };
+}
+
+selectors! {
+    // Shared
+    td => "td",
+    th => "th",
+    tr => "tr",
+    a => "a",
+    b => "b",
+    p => "p",
+    li => "li",
+    table => "table",
+    form => "form",
+    body => "body",
+
+    // CAS login page
+    cas_execution => "input[name=\"execution\"]",
+    /// Where Apereo CAS and the UPI theme put the reason a login failed.
+    cas_error => "#msg.errors, .errors, .alert-danger, .alert-warning, #msg",
+    cas_captcha => ".g-recaptcha, .h-captcha, [name='g-recaptcha-response'], input[name*='captcha'], img[src*='captcha']",
+    title => "title",
+    page_heading => "h1, h2",
+
+    // Dashboard and course list
+    profile_text => ".user-profile .profile-text",
+    course_row => "table > tbody > tr",
+
+    // Course detail
+    /// The warning shown when the lecturer has not set the course up yet.
+    course_unset => ".white-box.bg-warning",
+    rps_link => "a.btn-danger[href*='/mhs/rps/']",
+    white_box => ".white-box",
+    topic_block => ".container-fluid .block4",
+    topic_link => ".panel-body a.btn-info",
+    /// The disabled button that shows when a locked topic opens.
+    topic_access_button => ".panel-body div div button.disabled",
+
+    // Topic detail
+    topic_description => "#dashboard div",
+    topic_access_time => ".panel-heading p",
+    material => "#materi .row .col-lg-12",
+    task_table => "#tugas .table-striped",
+    task_modal => "#tugas .modal",
+    task_row => "tbody > tr",
+    panel_body => ".panel-body",
+    answer_file_link => "a[href*='/tugas/mhs']",
+    answer_delete_link => "a[href*='tugas_del']",
+    task_id_input => "input[name='id_tg']",
+    csrf_token_input => "input[name='_token']",
+    textarea => "textarea",
+    file_input => "input[type='file']",
+
+    // RPS
+    heading => "h1, h2, h3, h4, h5, h6",
+    rps_section_content => "table, ol, ul",
+}
+
+/// Compiles the selectors if needed and reports the first invalid one.
+///
+/// Called when a [`SpotClient`](crate::SpotClient) is built, so a broken
+/// selector fails there rather than halfway through a crawl.
+pub fn validate() -> Result<()> {
+    SELECTORS
+        .as_ref()
+        .map(|_| ())
+        .map_err(|e| ScraperError::InvalidConfig(e.clone()))
+}
+
+/// The compiled selectors.
+pub fn get() -> &'static Selectors {
+    SELECTORS
+        .as_ref()
+        .expect("selectors are checked by selectors::validate when the client is built")
+}
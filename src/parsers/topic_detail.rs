@@ -1,86 +1,100 @@
 // src/parsers/topic_detail.rs
 
+use super::content::{collect_embeds, parse_content_blocks};
+use super::selectors;
+use crate::dates::parse_datetime;
+use crate::embed::EmbedKind;
 use crate::error::Result;
-use crate::models::{Answer, Content, Task, TaskStatus, TopicDetail};
-use chrono::NaiveDateTime;
-use scraper::{CaseSensitivity, ElementRef, Html, Selector};
-
-fn parse_dt(s: &str) -> Option<NaiveDateTime> {
-    let t = s.trim();
-    [
-        "%d-%m-%Y %H:%M",
-        "%d-%m-%Y %H:%M:%S",
-        "%d/%m/%Y %H:%M",
-        "%d/%m/%Y %H:%M:%S",
-    ]
-    .iter()
-    .find_map(|fmt| NaiveDateTime::parse_from_str(t, fmt).ok())
+use crate::models::{Answer, Content, ContentBlock, Inline, Task, TaskForm, TopicDetail};
+use chrono::FixedOffset;
+use reqwest::Url;
+use scraper::{CaseSensitivity, ElementRef, Html};
+
+/// Helper untuk mengumpulkan sel `<td>` dari sebuah baris, sekali per baris.
+fn row_cells(row: ElementRef) -> Vec<ElementRef> {
+    row.select(&selectors::get().td).collect()
 }
 
 /// Helper untuk mendapatkan konten teks yang sudah di-trim dari elemen `<td>` berdasarkan indeksnya.
-fn get_td_content(row: ElementRef, index: usize) -> Option<String> {
-    row.select(&Selector::parse("td").unwrap())
-        .nth(index)
+fn get_td_content(cells: &[ElementRef], index: usize) -> Option<String> {
+    cells
+        .get(index)
         .map(|cell| cell.text().collect::<String>().trim().replace(": ", ""))
 }
 
 /// Helper untuk mendapatkan atribut `href` dari tag `<a>` di dalam `<td>`.
-fn get_td_file_href(row: ElementRef, index: usize) -> Option<String> {
-    row.select(&Selector::parse("td").unwrap())
-        .nth(index)?
-        .select(&Selector::parse("a").unwrap())
+fn get_td_file_href(cells: &[ElementRef], index: usize) -> Option<String> {
+    cells
+        .get(index)?
+        .select(&selectors::get().a)
         .next()?
         .value()
         .attr("href")
         .map(String::from)
 }
 
+/// Helper untuk mem-parsing isi `<td>` menjadi blok konten, tanpa awalan `:` dari tabel SPOT.
+fn get_td_blocks(cells: &[ElementRef], index: usize, base_url: &Url) -> Vec<ContentBlock> {
+    let Some(cell) = cells.get(index) else {
+        return Vec::new();
+    };
+    let mut blocks = parse_content_blocks(*cell, base_url);
+    if let Some(ContentBlock::Paragraph { inlines }) = blocks.first_mut()
+        && let Some(Inline::Text { text }) = inlines.first_mut()
+    {
+        *text = text.trim_start_matches(':').trim_start().to_string();
+        if text.is_empty() {
+            inlines.remove(0);
+        }
+        if inlines.is_empty() {
+            blocks.remove(0);
+        }
+    }
+    blocks
+}
+
 /// Fungsi utama untuk mem-parsing seluruh halaman detail topik.
 pub fn parse_topic_detail_from_html(
     html: &str,
     topic_id: u64,
     course_id: u64,
+    base_url: &Url,
+    zone: FixedOffset,
 ) -> Result<TopicDetail> {
     let document = Html::parse_document(html);
+    let s = selectors::get();
 
     let description = document
-        .select(&Selector::parse("#dashboard div").unwrap())
+        .select(&s.topic_description)
         .next()
         .map(|el| el.text().collect::<String>().trim().to_string());
     let access_time = document
-        .select(&Selector::parse(".panel-heading p").unwrap())
+        .select(&s.topic_access_time)
         .next()
-        .and_then(|p| parse_dt(&p.text().collect::<String>().replace("Waktu Akses:", "")));
+        .and_then(|p| parse_datetime(&p.text().collect::<String>(), zone));
 
-    let content_selector = Selector::parse("#materi .row .col-lg-12").unwrap();
     let contents: Vec<Content> = document
-        .select(&content_selector)
+        .select(&s.material)
         .enumerate()
         .map(|(index, content_el)| {
-            let youtube_id = content_el
-                .select(&Selector::parse("iframe[src*='youtube.com']").unwrap())
-                .next()
-                .and_then(|iframe| iframe.value().attr("src"))
-                .and_then(|src| {
-                    src.split("embed/")
-                        .last()?
-                        .split('?')
-                        .next()
-                        .map(String::from)
-                });
+            let embeds = collect_embeds(content_el, base_url);
+            let youtube_id = embeds.iter().find_map(|embed| match &embed.kind {
+                EmbedKind::YouTube { video_id } => Some(video_id.clone()),
+                _ => None,
+            });
             let raw_html = content_el.inner_html();
             Content {
                 id: index as u32,
                 youtube_id,
                 raw_html,
+                blocks: parse_content_blocks(content_el, base_url),
+                embeds,
             }
         })
         .collect();
 
-    let task_instruction_selector = Selector::parse("#tugas .table-striped").unwrap();
-    let task_modal_selector = Selector::parse("#tugas .modal").unwrap();
-    let task_tables: Vec<_> = document.select(&task_instruction_selector).collect();
-    let modals: Vec<_> = document.select(&task_modal_selector).collect();
+    let task_tables: Vec<_> = document.select(&s.task_table).collect();
+    let modals: Vec<_> = document.select(&s.task_modal).collect();
     let mut tasks = Vec::new();
 
     for (i, task_table) in task_tables.iter().enumerate() {
@@ -91,27 +105,30 @@ pub fn parse_topic_detail_from_html(
             token: String::new(),
             title: String::new(),
             description: String::new(),
+            description_blocks: Vec::new(),
             file: None,
             start_date: None,
             due_date: None,
-            status: TaskStatus::NotSubmitted,
             answer: None,
+            form: None,
         };
 
-        for row in task_table.select(&Selector::parse("tbody > tr").unwrap()) {
-            if let Some(header) = get_td_content(row, 0) {
+        for row in task_table.select(&s.task_row) {
+            let cells = row_cells(row);
+            if let Some(header) = get_td_content(&cells, 0) {
                 match header.as_str() {
-                    "Judul" => task.title = get_td_content(row, 1).unwrap_or_default(),
-                    "Deskripsi" => task.description = get_td_content(row, 1).unwrap_or_default(),
-                    "File" => task.file = get_td_file_href(row, 1),
+                    "Judul" => task.title = get_td_content(&cells, 1).unwrap_or_default(),
+                    "Deskripsi" => {
+                        task.description = get_td_content(&cells, 1).unwrap_or_default();
+                        task.description_blocks = get_td_blocks(&cells, 1, base_url);
+                    }
+                    "File" => task.file = get_td_file_href(&cells, 1),
                     "Waktu Pengumpulan" => {
-                        let dates: Vec<String> = row
-                            .select(&Selector::parse("b").unwrap())
-                            .map(|b| b.text().collect())
-                            .collect();
-                        if dates.len() >= 2 {
-                            task.start_date = parse_dt(&dates[0]);
-                            task.due_date = parse_dt(&dates[1]);
+                        let window: Vec<String> =
+                            row.select(&s.b).map(|b| b.text().collect()).collect();
+                        if window.len() >= 2 {
+                            task.start_date = parse_datetime(&window[0], zone);
+                            task.due_date = parse_datetime(&window[1], zone);
                         }
                     }
                     _ => {}
@@ -120,87 +137,97 @@ pub fn parse_topic_detail_from_html(
         }
 
         let next_element = task_table.next_siblings().find_map(ElementRef::wrap);
-        if let Some(sibling) = next_element {
-            if sibling
+        if let Some(sibling) = next_element
+            && sibling
                 .value()
                 .has_class("panel-info", CaseSensitivity::AsciiCaseInsensitive)
-            {
-                task.status = TaskStatus::Submitted;
-                let mut answer = Answer {
-                    id: None,
-                    content: String::new(),
-                    file_href: None,
-                    is_graded: false,
-                    lecturer_notes: String::new(),
-                    score: 0.0,
-                    date_submitted: None,
-                };
-
-                for row in sibling.select(&Selector::parse("tr").unwrap()) {
-                    if let Some(header) = get_td_content(row, 0) {
-                        match header.as_str() {
-                            "Waktu Pengumpulan" => {
-                                answer.date_submitted =
-                                    get_td_content(row, 1).and_then(|s| parse_dt(&s));
-                            }
-                            "Nilai" => {
-                                answer.score = get_td_content(row, 1)
-                                    .unwrap_or_default()
-                                    .parse()
-                                    .unwrap_or(0.0);
-                                answer.is_graded = true;
-                                task.status = TaskStatus::Graded;
-                            }
-                            "Catatan" => {
-                                answer.lecturer_notes = get_td_content(row, 1).unwrap_or_default()
-                            }
-                            _ => {}
+        {
+            let mut answer = Answer {
+                id: None,
+                content: String::new(),
+                file_href: None,
+                is_graded: false,
+                lecturer_notes: String::new(),
+                lecturer_notes_blocks: Vec::new(),
+                score: 0.0,
+                date_submitted: None,
+                delete_href: None,
+            };
+
+            for row in sibling.select(&s.tr) {
+                let cells = row_cells(row);
+                if let Some(header) = get_td_content(&cells, 0) {
+                    match header.as_str() {
+                        "Waktu Pengumpulan" => {
+                            answer.date_submitted =
+                                get_td_content(&cells, 1).and_then(|t| parse_datetime(&t, zone));
                         }
+                        "Nilai" => {
+                            answer.score = get_td_content(&cells, 1)
+                                .unwrap_or_default()
+                                .parse()
+                                .unwrap_or(0.0);
+                            answer.is_graded = true;
+                        }
+                        "Catatan" => {
+                            answer.lecturer_notes = get_td_content(&cells, 1).unwrap_or_default();
+                            answer.lecturer_notes_blocks = get_td_blocks(&cells, 1, base_url);
+                        }
+                        _ => {}
                     }
                 }
+            }
 
-                let panel_body_selector = Selector::parse(".panel-body").unwrap();
-                if let Some(body) = sibling.select(&panel_body_selector).next() {
-                    answer.content = body
-                        .children()
-                        .filter_map(|node| node.value().as_text())
-                        .map(|text| text.trim())
-                        .collect::<Vec<_>>()
-                        .join(" ");
-
-                    answer.file_href = body
-                        .select(&Selector::parse("a[href*='/tugas/mhs']").unwrap())
-                        .next()
-                        .and_then(|a| a.value().attr("href"))
-                        .map(|href| href.replace("/tugas/mhs", "/tugas"));
-
-                    if let Some(delete_link) = body
-                        .select(&Selector::parse("a[href*='tugas_del']").unwrap())
-                        .next()
-                    {
-                        answer.id = delete_link
-                            .value()
-                            .attr("href")
-                            .and_then(|h| h.split('/').last())
-                            .and_then(|v| v.parse::<u64>().ok());
-                    }
+            if let Some(body) = sibling.select(&s.panel_body).next() {
+                answer.content = body
+                    .children()
+                    .filter_map(|node| node.value().as_text())
+                    .map(|text| text.trim())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                answer.file_href = body
+                    .select(&s.answer_file_link)
+                    .next()
+                    .and_then(|a| a.value().attr("href"))
+                    .map(|href| href.replace("/tugas/mhs", "/tugas"));
+
+                if let Some(delete_link) = body.select(&s.answer_delete_link).next() {
+                    let delete_href = delete_link.value().attr("href");
+                    answer.id = delete_href
+                        .and_then(|h| h.split('/').next_back())
+                        .and_then(|v| v.parse::<u64>().ok());
+                    answer.delete_href = delete_href.map(String::from);
                 }
-                task.answer = Some(answer);
             }
+            task.answer = Some(answer);
         }
 
         if let Some(modal) = modals.get(i) {
             task.id = modal
-                .select(&Selector::parse("input[name='id_tg']").unwrap())
+                .select(&s.task_id_input)
                 .next()
                 .and_then(|inp| inp.value().attr("value"))
                 .and_then(|v| v.parse::<u64>().ok());
             task.token = modal
-                .select(&Selector::parse("input[name='_token']").unwrap())
+                .select(&s.csrf_token_input)
                 .next()
                 .and_then(|inp| inp.value().attr("value"))
                 .unwrap_or_default()
                 .to_string();
+            task.form = modal.select(&s.form).next().and_then(|form| {
+                let field_name = |selector| {
+                    form.select(selector)
+                        .next()
+                        .and_then(|el| el.value().attr("name"))
+                        .map(String::from)
+                };
+                Some(TaskForm {
+                    action: form.value().attr("action")?.to_string(),
+                    text_field: field_name(&s.textarea),
+                    file_field: field_name(&s.file_input),
+                })
+            });
         }
         tasks.push(task);
     }
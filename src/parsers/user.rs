@@ -1,29 +1,42 @@
-use crate::error::{Result, ScraperError};
+use super::selectors::{self, CSS};
+use super::{body_excerpt, excerpt};
+use crate::error::{PageElement, PageKind, ParseProblem, Result, ScraperError};
 use crate::models::User;
-use scraper::{Html, Selector};
+use scraper::Html;
 
 /// Parses the HTML of the main student dashboard page to extract user info.
 pub fn parse_user_from_html(html: &str) -> Result<User> {
     let document = Html::parse_document(html);
-    let selector = Selector::parse(".user-profile .profile-text").unwrap();
-
     let profile_element = document
-        .select(&selector)
+        .select(&selectors::get().profile_text)
         .next()
-        .ok_or_else(|| ScraperError::ElementNotFound("User profile text element".to_string()))?;
+        .ok_or_else(|| ScraperError::ElementNotFound {
+            page: PageKind::Dashboard,
+            element: PageElement::ProfileText,
+            selector: CSS.profile_text,
+            url: None,
+            excerpt: Some(body_excerpt(&document)),
+        })?;
+    let problem = |problem| ScraperError::ParsingError {
+        page: PageKind::Dashboard,
+        problem,
+        selector: Some(CSS.profile_text),
+        url: None,
+        excerpt: Some(excerpt(profile_element)),
+    };
 
     let profile_text = profile_element.text().collect::<String>();
-    let parts: Vec<&str> = profile_text.trim().split_whitespace().collect();
+    let parts: Vec<&str> = profile_text.split_whitespace().collect();
 
     let nim = match parts.last() {
         Some(n) => n.to_string(),
-        None => return Err(ScraperError::ParsingError("Could not extract NIM.".to_string())),
+        None => return Err(problem(ParseProblem::MissingNim)),
     };
 
     let name = parts[..parts.len() - 1].join(" ");
 
     if name.is_empty() {
-        return Err(ScraperError::ParsingError("Could not extract user name.".to_string()));
+        return Err(problem(ParseProblem::MissingName));
     }
 
     Ok(User { name, nim })
@@ -0,0 +1,181 @@
+// src/render.rs
+
+//! Turns parsed [`ContentBlock`]s into Markdown or plain text.
+
+use crate::models::{ContentBlock, Inline, ListItem};
+
+/// Renders blocks as CommonMark, keeping links, emphasis and list nesting.
+pub fn to_markdown(blocks: &[ContentBlock]) -> String {
+    blocks
+        .iter()
+        .map(markdown_block)
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Renders blocks as plain text, writing link targets in parentheses.
+pub fn to_plain_text(blocks: &[ContentBlock]) -> String {
+    blocks
+        .iter()
+        .map(plain_block)
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn markdown_block(block: &ContentBlock) -> String {
+    match block {
+        ContentBlock::Paragraph { inlines } => markdown_inlines(inlines),
+        ContentBlock::Heading { level, inlines } => format!(
+            "{} {}",
+            "#".repeat((*level).clamp(1, 6) as usize),
+            markdown_inlines(inlines)
+        ),
+        ContentBlock::List { ordered, items } => {
+            render_list(*ordered, items, markdown_inlines, &to_markdown)
+        }
+        ContentBlock::Image { src, alt } => format!(
+            "![{}]({})",
+            escape_markdown(alt.as_deref().unwrap_or_default()),
+            markdown_url(src)
+        ),
+        ContentBlock::Video { url, youtube_id } => match youtube_id {
+            Some(id) => format!("[YouTube video](https://www.youtube.com/watch?v={})", id),
+            None => format!("[Video]({})", markdown_url(url)),
+        },
+        ContentBlock::Embed { url } => format!("<{}>", url),
+        ContentBlock::File { name, url } => {
+            format!("[{}]({})", escape_markdown(name), markdown_url(url))
+        }
+        ContentBlock::Code { language, code } => {
+            let fence = code_fence(code);
+            format!(
+                "{}{}\n{}\n{}",
+                fence,
+                language.as_deref().unwrap_or_default(),
+                code,
+                fence
+            )
+        }
+    }
+}
+
+fn plain_block(block: &ContentBlock) -> String {
+    match block {
+        ContentBlock::Paragraph { inlines } | ContentBlock::Heading { inlines, .. } => {
+            plain_inlines(inlines)
+        }
+        ContentBlock::List { ordered, items } => {
+            render_list(*ordered, items, plain_inlines, &to_plain_text)
+        }
+        ContentBlock::Image { src, alt } => match alt {
+            Some(alt) => format!("{} ({})", alt, src),
+            None => src.clone(),
+        },
+        ContentBlock::Video { url, youtube_id } => match youtube_id {
+            Some(id) => format!("YouTube video: https://www.youtube.com/watch?v={}", id),
+            None => format!("Video: {}", url),
+        },
+        ContentBlock::Embed { url } => url.clone(),
+        ContentBlock::File { name, url } => format!("{} ({})", name, url),
+        ContentBlock::Code { code, .. } => code.clone(),
+    }
+}
+
+/// Renders list items with their markers, indenting nested content under them.
+fn render_list(
+    ordered: bool,
+    items: &[ListItem],
+    inlines: fn(&[Inline]) -> String,
+    blocks: &dyn Fn(&[ContentBlock]) -> String,
+) -> String {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let marker = if ordered {
+                format!("{}. ", i + 1)
+            } else {
+                "- ".to_string()
+            };
+            let indent = " ".repeat(marker.len());
+
+            let mut text = inlines(&item.inlines);
+            if !item.children.is_empty() {
+                text.push('\n');
+                text.push_str(&blocks(&item.children));
+            }
+            let mut lines = text.lines();
+            let mut rendered = format!("{}{}", marker, lines.next().unwrap_or_default());
+            for line in lines {
+                rendered.push('\n');
+                if !line.is_empty() {
+                    rendered.push_str(&indent);
+                    rendered.push_str(line);
+                }
+            }
+            rendered
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn markdown_inlines(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text { text } => escape_markdown(text),
+            Inline::Strong { text } => format!("**{}**", escape_markdown(text)),
+            Inline::Emphasis { text } => format!("*{}*", escape_markdown(text)),
+            Inline::Code { text } => {
+                let fence = if text.contains('`') { "``" } else { "`" };
+                format!("{}{}{}", fence, text, fence)
+            }
+            Inline::Link { text, url } if text == url => format!("<{}>", url),
+            Inline::Link { text, url } => {
+                format!("[{}]({})", escape_markdown(text), markdown_url(url))
+            }
+            Inline::LineBreak => "\\\n".to_string(),
+        })
+        .collect()
+}
+
+fn plain_inlines(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text { text }
+            | Inline::Strong { text }
+            | Inline::Emphasis { text }
+            | Inline::Code { text } => text.clone(),
+            Inline::Link { text, url } if text == url => url.clone(),
+            Inline::Link { text, url } => format!("{} ({})", text, url),
+            Inline::LineBreak => "\n".to_string(),
+        })
+        .collect()
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '[' | ']' | '`' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Makes a URL safe to use as a Markdown link destination.
+fn markdown_url(url: &str) -> String {
+    url.replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+/// Picks a backtick fence longer than any run of backticks inside `code`.
+fn code_fence(code: &str) -> String {
+    let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
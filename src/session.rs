@@ -0,0 +1,24 @@
+// src/session.rs
+
+use crate::error::{Result, ScraperError};
+use reqwest_cookie_store::{CookieStore, CookieStoreMutex};
+
+/// Rebuilds a cookie store from the JSON produced by [`export_cookie_store`].
+///
+/// Session cookies and expired cookies are kept on purpose: SPOT's session
+/// cookie has no expiry, and dropping it would log the user out.
+pub(crate) fn import_cookie_store(json: &str) -> Result<CookieStore> {
+    cookie_store::serde::json::load_all(json.as_bytes())
+        .map_err(|e| ScraperError::SessionError(e.to_string()))
+}
+
+/// Serializes every cookie held by `store` into JSON.
+pub(crate) fn export_cookie_store(store: &CookieStoreMutex) -> Result<String> {
+    let store = store
+        .lock()
+        .map_err(|_| ScraperError::SessionError("cookie store lock was poisoned".to_string()))?;
+    let mut buffer = Vec::new();
+    cookie_store::serde::json::save_incl_expired_and_nonpersistent(&store, &mut buffer)
+        .map_err(|e| ScraperError::SessionError(e.to_string()))?;
+    String::from_utf8(buffer).map_err(|e| ScraperError::SessionError(e.to_string()))
+}
@@ -0,0 +1,496 @@
+// src/storage.rs
+
+//! A local SQLite archive of everything scraped from SPOT.
+//!
+//! Each record is stored as JSON under its SPOT id, together with when it was
+//! first and last seen. Whenever a record's content changes, the new version is
+//! also appended to a history table, so earlier snapshots are never lost.
+
+use crate::client::SpotClient;
+use crate::error::{Result, ScraperError};
+use crate::models::{Answer, Course, DetailCourse, Task, TopicDetail, TopicInfo, User};
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS users (
+        id TEXT PRIMARY KEY, parent TEXT, data TEXT NOT NULL,
+        first_seen TEXT NOT NULL, last_seen TEXT NOT NULL);
+    CREATE TABLE IF NOT EXISTS courses (
+        id TEXT PRIMARY KEY, parent TEXT, data TEXT NOT NULL,
+        first_seen TEXT NOT NULL, last_seen TEXT NOT NULL);
+    CREATE TABLE IF NOT EXISTS course_details (
+        id TEXT PRIMARY KEY, parent TEXT, data TEXT NOT NULL,
+        first_seen TEXT NOT NULL, last_seen TEXT NOT NULL);
+    CREATE TABLE IF NOT EXISTS topics (
+        id TEXT PRIMARY KEY, parent TEXT, data TEXT NOT NULL,
+        first_seen TEXT NOT NULL, last_seen TEXT NOT NULL);
+    CREATE TABLE IF NOT EXISTS tasks (
+        id TEXT PRIMARY KEY, parent TEXT, data TEXT NOT NULL,
+        first_seen TEXT NOT NULL, last_seen TEXT NOT NULL);
+    CREATE TABLE IF NOT EXISTS answers (
+        id TEXT PRIMARY KEY, parent TEXT, data TEXT NOT NULL,
+        first_seen TEXT NOT NULL, last_seen TEXT NOT NULL);
+    CREATE TABLE IF NOT EXISTS history (
+        kind TEXT NOT NULL, id TEXT NOT NULL, data TEXT NOT NULL,
+        recorded_at TEXT NOT NULL);
+    CREATE INDEX IF NOT EXISTS history_record ON history (kind, id);
+    CREATE TABLE IF NOT EXISTS topic_fetches (
+        id TEXT PRIMARY KEY, fetched_at TEXT NOT NULL);
+";
+
+/// How long [`Storage::sync`] trusts a settled topic before fetching it again.
+const DEFAULT_MAX_TOPIC_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The kinds of records kept in the archive, one table each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordKind {
+    User,
+    Course,
+    CourseDetail,
+    Topic,
+    Task,
+    Answer,
+}
+
+impl RecordKind {
+    fn table(self) -> &'static str {
+        match self {
+            RecordKind::User => "users",
+            RecordKind::Course => "courses",
+            RecordKind::CourseDetail => "course_details",
+            RecordKind::Topic => "topics",
+            RecordKind::Task => "tasks",
+            RecordKind::Answer => "answers",
+        }
+    }
+}
+
+/// A stored record with the times it was first and last seen on SPOT.
+#[derive(Debug, Clone)]
+pub struct SeenRecord<T> {
+    pub record: T,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// An earlier version of a record, as it was when first seen with this content.
+#[derive(Debug, Clone)]
+pub struct Revision<T> {
+    pub record: T,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// What a [`Storage::sync`] run fetched and stored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    pub courses: usize,
+    /// Topics whose page was fetched again.
+    pub topics_fetched: usize,
+    /// Topics that were unchanged and not refetched.
+    pub topics_skipped: usize,
+    /// Records that were new or whose content changed.
+    pub changed_records: usize,
+}
+
+pub struct Storage {
+    conn: Mutex<Connection>,
+    max_topic_age: Duration,
+}
+
+impl Storage {
+    /// Opens (or creates) the archive at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path).map_err(storage_error)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory().map_err(storage_error)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA).map_err(storage_error)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            max_topic_age: DEFAULT_MAX_TOPIC_AGE,
+        })
+    }
+
+    /// Refetches every topic last fetched longer than `age` ago, even if its
+    /// access time did not change. Defaults to one day.
+    ///
+    /// New tasks, answers, grades and edited descriptions do not show on the
+    /// course page, so this bounds how stale the archive of a topic can get.
+    pub fn max_topic_age(mut self, age: Duration) -> Self {
+        self.max_topic_age = age;
+        self
+    }
+
+    fn conn(&self) -> Result<MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|_| ScraperError::StorageError("database lock was poisoned".to_string()))
+    }
+
+    /// Fetches the profile, courses and course details, and refetches only the
+    /// topics that are new, whose access time or accessibility changed, or that
+    /// were last fetched longer than [`max_topic_age`](Self::max_topic_age) ago.
+    ///
+    /// The database calls are blocking and run on the calling task; they are
+    /// short, but on a single-threaded runtime they hold up other tasks.
+    pub async fn sync(&self, client: &SpotClient) -> Result<SyncReport> {
+        let mut report = SyncReport::default();
+
+        let user = client.get_user_profile().await?;
+        report.changed_records += self.save_user(&user)?;
+
+        for course in client.get_courses().await? {
+            report.courses += 1;
+            report.changed_records += self.save_course(&course)?;
+
+            let previous = self
+                .course_detail(course.id)?
+                .map(|stored| stored.record.topics)
+                .unwrap_or_default();
+            let detail = client.get_course_detail(&course).await?;
+            report.changed_records += self.save_course_detail(&detail)?;
+
+            for topic in detail.topics.iter().filter(|t| t.is_accessible) {
+                let Some(topic_id) = topic.id else {
+                    continue;
+                };
+                let before = previous.iter().find(|t| t.id == topic.id);
+                if !self.topic_needs_refresh(topic_id, topic, before)? {
+                    self.touch_topic(topic_id)?;
+                    report.topics_skipped += 1;
+                    continue;
+                }
+
+                let topic_detail = client.get_topic_detail(topic).await?;
+                report.changed_records += self.save_topic_detail(&topic_detail, course.id)?;
+                self.record_topic_fetch(topic_id)?;
+                report.topics_fetched += 1;
+            }
+        }
+        Ok(report)
+    }
+
+    fn topic_needs_refresh(
+        &self,
+        topic_id: u64,
+        topic: &TopicInfo,
+        before: Option<&TopicInfo>,
+    ) -> Result<bool> {
+        let Some(before) = before else {
+            return Ok(true);
+        };
+        if before.access_time != topic.access_time || before.is_accessible != topic.is_accessible {
+            return Ok(true);
+        }
+        if self.topic_detail(topic_id)?.is_none() {
+            return Ok(true);
+        }
+        let max_age = chrono::Duration::from_std(self.max_topic_age)
+            .map_err(|e| ScraperError::StorageError(e.to_string()))?;
+        Ok(self
+            .topic_fetched_at(topic_id)?
+            .is_none_or(|fetched_at| now() - fetched_at >= max_age))
+    }
+
+    fn record_topic_fetch(&self, topic_id: u64) -> Result<()> {
+        self.conn()?
+            .execute(
+                "INSERT INTO topic_fetches (id, fetched_at) VALUES (?1, ?2)
+                 ON CONFLICT (id) DO UPDATE SET fetched_at = excluded.fetched_at",
+                params![topic_id.to_string(), now()],
+            )
+            .map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn topic_fetched_at(&self, topic_id: u64) -> Result<Option<DateTime<Utc>>> {
+        self.conn()?
+            .query_row(
+                "SELECT fetched_at FROM topic_fetches WHERE id = ?1",
+                params![topic_id.to_string()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(storage_error)
+    }
+
+    /// Marks a topic and its tasks and answers as seen now, without changing them.
+    fn touch_topic(&self, topic_id: u64) -> Result<()> {
+        let now = now();
+        let id = topic_id.to_string();
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE topics SET last_seen = ?1 WHERE id = ?2",
+            params![now, id],
+        )
+        .map_err(storage_error)?;
+        conn.execute(
+            "UPDATE answers SET last_seen = ?1
+             WHERE parent IN (SELECT id FROM tasks WHERE parent = ?2)",
+            params![now, id],
+        )
+        .map_err(storage_error)?;
+        conn.execute(
+            "UPDATE tasks SET last_seen = ?1 WHERE parent = ?2",
+            params![now, id],
+        )
+        .map_err(storage_error)?;
+        Ok(())
+    }
+
+    /// Stores the profile. Returns how many records were new or changed.
+    pub fn save_user(&self, user: &User) -> Result<usize> {
+        self.upsert(RecordKind::User, &user.nim, None, user)
+    }
+
+    pub fn save_course(&self, course: &Course) -> Result<usize> {
+        self.upsert(RecordKind::Course, &course.id.to_string(), None, course)
+    }
+
+    pub fn save_course_detail(&self, detail: &DetailCourse) -> Result<usize> {
+        let id = detail.course_info.id.to_string();
+        self.upsert(RecordKind::CourseDetail, &id, None, detail)
+    }
+
+    /// Stores a topic together with its tasks and their answers.
+    ///
+    /// Task CSRF tokens change on every page load, so they are not stored.
+    /// Neither is the task status, which changes with the clock rather than
+    /// with SPOT and is derived again when the task is loaded.
+    pub fn save_topic_detail(&self, topic: &TopicDetail, course_id: u64) -> Result<usize> {
+        let topic_id = topic.id.to_string();
+        let stored_topic = TopicDetail {
+            tasks: Vec::new(),
+            ..topic.clone()
+        };
+        let mut changed = self.upsert(
+            RecordKind::Topic,
+            &topic_id,
+            Some(&course_id.to_string()),
+            &stored_topic,
+        )?;
+
+        for task in &topic.tasks {
+            let Some(task_id) = task.id else {
+                continue;
+            };
+            let task_id = task_id.to_string();
+            let stored_task = Task {
+                token: String::new(),
+                ..task.clone()
+            };
+            let mut stored_task = serde_json::to_value(&stored_task)
+                .map_err(|e| ScraperError::StorageError(e.to_string()))?;
+            if let Some(fields) = stored_task.as_object_mut() {
+                fields.remove("status");
+            }
+            changed += self.upsert(RecordKind::Task, &task_id, Some(&topic_id), &stored_task)?;
+
+            if let Some(answer) = &task.answer
+                && let Some(answer_id) = answer.id
+            {
+                changed += self.upsert(
+                    RecordKind::Answer,
+                    &answer_id.to_string(),
+                    Some(&task_id),
+                    answer,
+                )?;
+            }
+        }
+        Ok(changed)
+    }
+
+    pub fn user(&self, nim: &str) -> Result<Option<SeenRecord<User>>> {
+        self.load(RecordKind::User, nim)
+    }
+
+    pub fn courses(&self) -> Result<Vec<SeenRecord<Course>>> {
+        self.load_where(RecordKind::Course, None)
+    }
+
+    pub fn course_detail(&self, course_id: u64) -> Result<Option<SeenRecord<DetailCourse>>> {
+        self.load(RecordKind::CourseDetail, &course_id.to_string())
+    }
+
+    /// Loads a topic with the tasks last stored for it.
+    pub fn topic_detail(&self, topic_id: u64) -> Result<Option<SeenRecord<TopicDetail>>> {
+        let Some(mut topic) = self.load::<TopicDetail>(RecordKind::Topic, &topic_id.to_string())?
+        else {
+            return Ok(None);
+        };
+        topic.record.tasks = self
+            .tasks_of_topic(topic_id)?
+            .into_iter()
+            .map(|task| task.record)
+            .collect();
+        Ok(Some(topic))
+    }
+
+    pub fn tasks(&self) -> Result<Vec<SeenRecord<Task>>> {
+        self.load_where(RecordKind::Task, None)
+    }
+
+    fn tasks_of_topic(&self, topic_id: u64) -> Result<Vec<SeenRecord<Task>>> {
+        self.load_where(RecordKind::Task, Some(("parent", &topic_id.to_string())))
+    }
+
+    pub fn answer(&self, answer_id: u64) -> Result<Option<SeenRecord<Answer>>> {
+        self.load(RecordKind::Answer, &answer_id.to_string())
+    }
+
+    /// Returns every distinct version of a record, oldest first.
+    pub fn history<T: DeserializeOwned>(
+        &self,
+        kind: RecordKind,
+        id: &str,
+    ) -> Result<Vec<Revision<T>>> {
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT data, recorded_at FROM history
+                 WHERE kind = ?1 AND id = ?2 ORDER BY rowid",
+            )
+            .map_err(storage_error)?;
+        let rows = statement
+            .query_map(params![kind.table(), id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get(1)?))
+            })
+            .map_err(storage_error)?;
+
+        rows.map(|row| {
+            let (data, recorded_at) = row.map_err(storage_error)?;
+            Ok(Revision {
+                record: from_json(&data)?,
+                recorded_at,
+            })
+        })
+        .collect()
+    }
+
+    /// Inserts or updates a record, appending to its history when its content
+    /// changed. Returns 1 if the record was new or changed, 0 otherwise.
+    fn upsert<T: Serialize>(
+        &self,
+        kind: RecordKind,
+        id: &str,
+        parent: Option<&str>,
+        record: &T,
+    ) -> Result<usize> {
+        let data =
+            serde_json::to_string(record).map_err(|e| ScraperError::StorageError(e.to_string()))?;
+        let now = now();
+        let table = kind.table();
+
+        let mut conn = self.conn()?;
+        let tx = conn.transaction().map_err(storage_error)?;
+        let existing: Option<String> = tx
+            .query_row(
+                &format!("SELECT data FROM {} WHERE id = ?1", table),
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(storage_error)?;
+
+        let changed = match existing {
+            None => {
+                tx.execute(
+                    &format!(
+                        "INSERT INTO {} (id, parent, data, first_seen, last_seen)
+                         VALUES (?1, ?2, ?3, ?4, ?4)",
+                        table
+                    ),
+                    params![id, parent, data, now],
+                )
+                .map_err(storage_error)?;
+                true
+            }
+            Some(existing) => {
+                tx.execute(
+                    &format!(
+                        "UPDATE {} SET parent = ?2, data = ?3, last_seen = ?4 WHERE id = ?1",
+                        table
+                    ),
+                    params![id, parent, data, now],
+                )
+                .map_err(storage_error)?;
+                existing != data
+            }
+        };
+        if changed {
+            tx.execute(
+                "INSERT INTO history (kind, id, data, recorded_at) VALUES (?1, ?2, ?3, ?4)",
+                params![table, id, data, now],
+            )
+            .map_err(storage_error)?;
+        }
+        tx.commit().map_err(storage_error)?;
+        Ok(changed as usize)
+    }
+
+    fn load<T: DeserializeOwned>(
+        &self,
+        kind: RecordKind,
+        id: &str,
+    ) -> Result<Option<SeenRecord<T>>> {
+        Ok(self.load_where(kind, Some(("id", id)))?.into_iter().next())
+    }
+
+    /// Loads the records of `kind`, optionally only those whose `column` equals a value.
+    fn load_where<T: DeserializeOwned>(
+        &self,
+        kind: RecordKind,
+        filter: Option<(&str, &str)>,
+    ) -> Result<Vec<SeenRecord<T>>> {
+        let condition = filter
+            .map(|(column, _)| format!("WHERE {} = ?1", column))
+            .unwrap_or_default();
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT data, first_seen, last_seen FROM {} {} ORDER BY first_seen, rowid",
+                kind.table(),
+                condition
+            ))
+            .map_err(storage_error)?;
+        let map_row =
+            |row: &rusqlite::Row| Ok((row.get::<_, String>(0)?, row.get(1)?, row.get(2)?));
+        let rows = match filter {
+            Some((_, value)) => statement.query_map(params![value], map_row),
+            None => statement.query_map([], map_row),
+        }
+        .map_err(storage_error)?;
+
+        rows.map(|row| {
+            let (data, first_seen, last_seen) = row.map_err(storage_error)?;
+            Ok(SeenRecord {
+                record: from_json(&data)?,
+                first_seen,
+                last_seen,
+            })
+        })
+        .collect()
+    }
+}
+
+fn now() -> DateTime<Utc> {
+    Utc::now()
+}
+
+fn from_json<T: DeserializeOwned>(data: &str) -> Result<T> {
+    serde_json::from_str(data).map_err(|e| ScraperError::StorageError(e.to_string()))
+}
+
+fn storage_error(e: rusqlite::Error) -> ScraperError {
+    ScraperError::StorageError(e.to_string())
+}
@@ -0,0 +1,61 @@
+// src/tasks.rs
+
+use crate::models::{CourseTask, TaskStatus};
+
+/// Selects and orders the tasks returned by
+/// [`SpotClient::get_tasks`](crate::SpotClient::get_tasks).
+#[derive(Debug, Clone, Default)]
+pub struct TaskFilter {
+    statuses: Vec<TaskStatus>,
+    sort_by_due_date: bool,
+}
+
+impl TaskFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tasks that still need an answer and can still get one: those that are
+    /// [`Pending`](TaskStatus::Pending), [`NotSubmitted`](TaskStatus::NotSubmitted)
+    /// or [`NotYetOpen`](TaskStatus::NotYetOpen), ordered by due date.
+    ///
+    /// Tasks past their deadline are left out; add them with
+    /// [`overdue`](Self::overdue).
+    pub fn pending() -> Self {
+        Self::new()
+            .status(TaskStatus::Pending)
+            .status(TaskStatus::NotSubmitted)
+            .status(TaskStatus::NotYetOpen)
+            .sort_by_due_date()
+    }
+
+    /// Also keeps [`Overdue`](TaskStatus::Overdue) tasks.
+    pub fn overdue(self) -> Self {
+        self.status(TaskStatus::Overdue)
+    }
+
+    /// Keeps tasks with `status`. Calling this several times keeps tasks with
+    /// any of the given statuses; never calling it keeps every task.
+    pub fn status(mut self, status: TaskStatus) -> Self {
+        self.statuses.push(status);
+        self
+    }
+
+    /// Orders tasks by due date, earliest first, with undated tasks last.
+    pub fn sort_by_due_date(mut self) -> Self {
+        self.sort_by_due_date = true;
+        self
+    }
+
+    pub fn matches(&self, task: &CourseTask) -> bool {
+        self.statuses.is_empty() || self.statuses.contains(&task.task.status())
+    }
+
+    pub fn apply(&self, mut tasks: Vec<CourseTask>) -> Vec<CourseTask> {
+        tasks.retain(|task| self.matches(task));
+        if self.sort_by_due_date {
+            tasks.sort_by_key(|task| (task.task.due_date.is_none(), task.task.due_date));
+        }
+        tasks
+    }
+}
@@ -0,0 +1,165 @@
+// src/throttle.rs
+
+//! Per-host rate limiting and a cap on concurrent requests, shared by every
+//! request a [`SpotClient`](crate::SpotClient) sends.
+//!
+//! The limits apply to the requests the client sends, not to the redirects
+//! reqwest follows for them: the CAS to SPOT hop after a login takes no token
+//! from SPOT's bucket. A redirected request keeps its request slot throughout.
+
+use reqwest::{Response, Url};
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::time::Instant;
+
+/// How fast requests to one host may be sent, as a token bucket: up to `burst`
+/// requests at once, refilled at `per_second` requests per second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    per_second: f64,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// Allows `requests` per second, one at a time. Use [`burst`](Self::burst)
+    /// to let idle time build up into a short burst.
+    pub fn per_second(requests: f64) -> Self {
+        Self {
+            per_second: requests,
+            burst: 1,
+        }
+    }
+
+    /// Sends requests as fast as the server answers them.
+    pub fn unlimited() -> Self {
+        Self::per_second(f64::INFINITY)
+    }
+
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    fn is_unlimited(&self) -> bool {
+        !self.per_second.is_finite() || self.per_second <= 0.0
+    }
+}
+
+impl Default for RateLimit {
+    /// Four requests per second with bursts of up to eight, which keeps a full
+    /// crawl quick without hammering SPOT.
+    fn default() -> Self {
+        Self::per_second(4.0).burst(8)
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+pub(crate) struct Throttle {
+    default_limit: RateLimit,
+    host_limits: HashMap<String, RateLimit>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+    concurrency: Semaphore,
+}
+
+impl Throttle {
+    pub(crate) fn new(
+        default_limit: RateLimit,
+        host_limits: HashMap<String, RateLimit>,
+        max_concurrency: usize,
+    ) -> Self {
+        Self {
+            default_limit,
+            host_limits,
+            buckets: Mutex::new(HashMap::new()),
+            concurrency: Semaphore::new(max_concurrency.max(1)),
+        }
+    }
+
+    /// Waits for a token from the bucket of `url`'s host, then for a free
+    /// request slot. The request may be sent and its body read while the
+    /// permit is held.
+    pub(crate) async fn acquire(&self, url: &Url) -> SemaphorePermit<'_> {
+        let wait = self.reserve(url.host_str().unwrap_or_default());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        self.concurrency
+            .acquire()
+            .await
+            .expect("the request semaphore is never closed")
+    }
+
+    /// Takes a token from `host`'s bucket and returns how long to wait before
+    /// it may be used. Tokens are reserved up front, so waiting requests are
+    /// released in the order they arrived.
+    fn reserve(&self, host: &str) -> Duration {
+        let limit = self
+            .host_limits
+            .get(host)
+            .copied()
+            .unwrap_or(self.default_limit);
+        if limit.is_unlimited() {
+            return Duration::ZERO;
+        }
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(host.to_string()).or_insert(Bucket {
+            tokens: limit.burst as f64,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * limit.per_second).min(limit.burst as f64);
+        bucket.updated = now;
+        bucket.tokens -= 1.0;
+
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / limit.per_second)
+        }
+    }
+}
+
+/// A response together with the request slot it was sent in. The slot is
+/// freed once the body has been read or the response is dropped, so the
+/// concurrency cap also bounds how many bodies are downloaded at once.
+pub(crate) struct Throttled<'a> {
+    response: Response,
+    _permit: SemaphorePermit<'a>,
+}
+
+impl<'a> Throttled<'a> {
+    pub(crate) fn new(response: Response, permit: SemaphorePermit<'a>) -> Self {
+        Self {
+            response,
+            _permit: permit,
+        }
+    }
+
+    /// Reads the whole body as text, releasing the slot afterwards.
+    pub(crate) async fn text(self) -> reqwest::Result<String> {
+        self.response.text().await
+    }
+}
+
+impl Deref for Throttled<'_> {
+    type Target = Response;
+
+    fn deref(&self) -> &Response {
+        &self.response
+    }
+}
+
+impl DerefMut for Throttled<'_> {
+    fn deref_mut(&mut self) -> &mut Response {
+        &mut self.response
+    }
+}
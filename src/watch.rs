@@ -0,0 +1,407 @@
+// src/watch.rs
+
+//! A long-running watcher that polls SPOT and posts changes to webhooks.
+
+use crate::client::SpotClient;
+use crate::diff::{ChangeEvent, diff};
+use crate::error::{Result, ScraperError};
+use crate::files::write_atomic;
+use crate::models::Snapshot;
+use crate::retry::{random_duration, retry_after};
+use chrono::{DateTime, FixedOffset, Utc};
+use reqwest::{StatusCode, Url};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const DEFAULT_JITTER: Duration = Duration::from_secs(60);
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(2);
+const DEFAULT_MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(30);
+/// Discord rejects messages longer than this many characters.
+const DISCORD_MAX_CONTENT: usize = 2000;
+
+/// How change events are laid out in the webhook request body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WebhookFormat {
+    /// `{"detected_at": ..., "events": [...]}` with the events as typed JSON.
+    #[default]
+    Json,
+    /// A Slack incoming webhook message: `{"text": ...}`.
+    Slack,
+    /// A Discord webhook message: `{"content": ...}`.
+    Discord,
+}
+
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    pub url: Url,
+    pub format: WebhookFormat,
+}
+
+/// The outcome of one [`Watcher::poll_once`].
+#[derive(Debug, Clone, Default)]
+pub struct PollReport {
+    /// Changes since the previous poll. Empty on the first poll, which only
+    /// records the baseline.
+    pub events: Vec<ChangeEvent>,
+    pub delivered: usize,
+    /// Deliveries that gave up and were written to the dead-letter file.
+    pub failed: usize,
+    /// Failed deliveries that could not be written to the dead-letter file
+    /// either. They are lost; the poll itself still counts as done.
+    pub dead_letter_errors: Vec<String>,
+    /// Why the state file could not be read, if it could not. The poll then
+    /// starts from a new baseline and overwrites the file.
+    pub discarded_state: Option<String>,
+}
+
+/// A delivery that failed every attempt, as written to the dead-letter file.
+#[derive(Debug, Serialize)]
+struct DeadLetter<'a> {
+    failed_at: DateTime<Utc>,
+    url: &'a str,
+    error: String,
+    payload: &'a Value,
+}
+
+/// Configures and creates a [`Watcher`].
+pub struct WatcherBuilder {
+    client: SpotClient,
+    interval: Duration,
+    jitter: Duration,
+    webhooks: Vec<Webhook>,
+    max_attempts: u32,
+    retry_delay: Duration,
+    max_retry_delay: Duration,
+    dead_letter_file: Option<PathBuf>,
+    state_file: Option<PathBuf>,
+}
+
+impl WatcherBuilder {
+    /// Sets the time between polls. Defaults to 15 minutes.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Adds a random delay of up to `jitter` to every interval, so polls do not
+    /// hit SPOT at the same second every time. Defaults to one minute.
+    pub fn jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn webhook(mut self, url: &str, format: WebhookFormat) -> Result<Self> {
+        let url = Url::parse(url).map_err(|e| {
+            ScraperError::InvalidConfig(format!("invalid webhook URL {}: {}", url, e))
+        })?;
+        self.webhooks.push(Webhook { url, format });
+        Ok(self)
+    }
+
+    /// Sets how many times a webhook delivery is attempted. Defaults to 5.
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry; it doubles after each attempt.
+    /// Defaults to 2 seconds.
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Caps the delay between attempts, including delays a webhook asks for
+    /// with `Retry-After`. Defaults to one minute.
+    pub fn max_retry_delay(mut self, delay: Duration) -> Self {
+        self.max_retry_delay = delay;
+        self
+    }
+
+    /// Appends deliveries that failed every attempt to `path`, one JSON object per line.
+    pub fn dead_letter_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.dead_letter_file = Some(path.into());
+        self
+    }
+
+    /// Keeps the last snapshot in `path`, so a restarted watcher reports what
+    /// changed while it was down instead of starting from a new baseline.
+    pub fn state_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.state_file = Some(path.into());
+        self
+    }
+
+    pub fn build(self) -> Result<Watcher> {
+        let http = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()?;
+        Ok(Watcher {
+            client: self.client,
+            http,
+            interval: self.interval,
+            jitter: self.jitter,
+            webhooks: self.webhooks,
+            max_attempts: self.max_attempts,
+            retry_delay: self.retry_delay,
+            max_retry_delay: self.max_retry_delay,
+            dead_letter_file: self.dead_letter_file,
+            state_file: self.state_file,
+            state_loaded: false,
+            last: None,
+        })
+    }
+}
+
+/// Polls SPOT on a schedule and posts every [`ChangeEvent`] to the configured webhooks.
+pub struct Watcher {
+    client: SpotClient,
+    http: reqwest::Client,
+    interval: Duration,
+    jitter: Duration,
+    webhooks: Vec<Webhook>,
+    max_attempts: u32,
+    retry_delay: Duration,
+    max_retry_delay: Duration,
+    dead_letter_file: Option<PathBuf>,
+    state_file: Option<PathBuf>,
+    /// Whether the state file was read, or found damaged and ignored.
+    state_loaded: bool,
+    last: Option<Snapshot>,
+}
+
+impl Watcher {
+    pub fn builder(client: SpotClient) -> WatcherBuilder {
+        WatcherBuilder {
+            client,
+            interval: DEFAULT_INTERVAL,
+            jitter: DEFAULT_JITTER,
+            webhooks: Vec::new(),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            max_retry_delay: DEFAULT_MAX_RETRY_DELAY,
+            dead_letter_file: None,
+            state_file: None,
+        }
+    }
+
+    pub fn client(&self) -> &SpotClient {
+        &self.client
+    }
+
+    /// Takes a snapshot, compares it with the previous one and delivers the changes.
+    ///
+    /// The new snapshot becomes the baseline even if some deliveries failed,
+    /// so webhooks that did receive the changes never get them twice.
+    pub async fn poll_once(&mut self) -> Result<PollReport> {
+        let mut report = PollReport::default();
+        if !self.state_loaded
+            && let Some(path) = &self.state_file
+        {
+            match load_state(path).await {
+                Ok(state) => self.last = state,
+                // A damaged file would otherwise fail every poll from now on.
+                Err(ScraperError::InvalidSnapshot(e)) => report.discarded_state = Some(e),
+                Err(e) => return Err(e),
+            }
+            self.state_loaded = true;
+        }
+
+        let snapshot = self.client.snapshot().await?;
+        if let Some(last) = &self.last {
+            report.events = diff(last, &snapshot);
+        }
+
+        if !report.events.is_empty() {
+            for webhook in &self.webhooks {
+                let payload = payload(webhook.format, snapshot.taken_at, &report.events);
+                match self.deliver(&webhook.url, &payload).await {
+                    Ok(()) => report.delivered += 1,
+                    Err(e) => {
+                        report.failed += 1;
+                        if let Err(e) = self.dead_letter(&webhook.url, &e, &payload).await {
+                            report.dead_letter_errors.push(e.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(path) = &self.state_file {
+            let json = serde_json::to_string(&snapshot)
+                .map_err(|e| ScraperError::InvalidSnapshot(e.to_string()))?;
+            write_atomic(path, json.as_bytes()).await?;
+        }
+        self.last = Some(snapshot);
+        Ok(report)
+    }
+
+    /// Polls forever. Errors from a single poll, such as SPOT being down, are
+    /// passed to `on_error` and the watcher carries on at the next interval.
+    /// So are a damaged state file and dead letters that could not be written.
+    pub async fn run(&mut self, on_error: impl FnMut(&ScraperError)) {
+        self.run_until(std::future::pending(), on_error).await
+    }
+
+    /// Like [`run`](Self::run), but returns once `shutdown` completes.
+    pub async fn run_until(
+        &mut self,
+        shutdown: impl Future<Output = ()>,
+        mut on_error: impl FnMut(&ScraperError),
+    ) {
+        tokio::pin!(shutdown);
+        loop {
+            match self.poll_once().await {
+                Ok(report) => {
+                    if let Some(e) = report.discarded_state {
+                        on_error(&ScraperError::InvalidSnapshot(format!(
+                            "ignored the damaged state file: {}",
+                            e
+                        )));
+                    }
+                    for e in report.dead_letter_errors {
+                        on_error(&ScraperError::IoError(std::io::Error::other(format!(
+                            "could not write a dead letter: {}",
+                            e
+                        ))));
+                    }
+                }
+                Err(e) => on_error(&e),
+            }
+            let delay = self.interval + random_duration(self.jitter);
+            tokio::select! {
+                _ = &mut shutdown => return,
+                _ = tokio::time::sleep(delay) => {}
+            }
+        }
+    }
+
+    /// Posts `payload`, retrying network errors, 5xx responses and 429s with
+    /// exponential backoff. A `Retry-After` header overrides the backoff, up
+    /// to the maximum retry delay.
+    async fn deliver(&self, url: &Url, payload: &Value) -> std::result::Result<(), String> {
+        let mut delay = self.retry_delay;
+        let mut attempt = 1;
+        loop {
+            let (error, retry_after) = match self.http.post(url.clone()).json(payload).send().await
+            {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => {
+                    let status = response.status();
+                    let retryable =
+                        status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+                    if !retryable {
+                        return Err(format!("webhook responded with {}", status));
+                    }
+                    (
+                        format!("webhook responded with {}", status),
+                        retry_after(response.headers()),
+                    )
+                }
+                Err(e) => (e.to_string(), None),
+            };
+
+            if attempt >= self.max_attempts {
+                return Err(format!("{} (after {} attempts)", error, attempt));
+            }
+            tokio::time::sleep(retry_after.unwrap_or(delay).min(self.max_retry_delay)).await;
+            delay = (delay * 2).min(self.max_retry_delay);
+            attempt += 1;
+        }
+    }
+
+    async fn dead_letter(&self, url: &Url, error: &str, payload: &Value) -> Result<()> {
+        let Some(path) = &self.dead_letter_file else {
+            return Ok(());
+        };
+        let letter = DeadLetter {
+            failed_at: Utc::now(),
+            url: url.as_str(),
+            error: error.to_string(),
+            payload,
+        };
+        let mut line = serde_json::to_string(&letter)
+            .map_err(|e| ScraperError::InvalidSnapshot(e.to_string()))?;
+        line.push('\n');
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+fn payload(
+    format: WebhookFormat,
+    detected_at: DateTime<FixedOffset>,
+    events: &[ChangeEvent],
+) -> Value {
+    let lines: Vec<String> = events.iter().map(|event| format!("• {}", event)).collect();
+    match format {
+        WebhookFormat::Json => json!({ "detected_at": detected_at, "events": events }),
+        WebhookFormat::Slack => json!({ "text": lines.join("\n") }),
+        WebhookFormat::Discord => json!({ "content": discord_content(&lines) }),
+    }
+}
+
+/// Joins `lines` into one Discord message. Lines that do not fit are left
+/// out and counted in a closing "…and N more changes" line, so a long diff
+/// never loses events without saying so.
+fn discord_content(lines: &[String]) -> String {
+    // Room for the closing line, whatever the count.
+    const MORE_LINE: usize = 32;
+    let mut content = String::new();
+    let mut length = 0;
+    let mut included = 0;
+    for (i, line) in lines.iter().enumerate() {
+        let limit = if i + 1 == lines.len() {
+            DISCORD_MAX_CONTENT
+        } else {
+            DISCORD_MAX_CONTENT - MORE_LINE
+        };
+        let separator = usize::from(included > 0);
+        let line_length = line.chars().count();
+        if length + separator + line_length > limit {
+            if included == 0 {
+                // A single event too long for a message is cut short.
+                content = line.chars().take(limit - 1).collect();
+                content.push('…');
+                included = 1;
+            }
+            break;
+        }
+        if separator == 1 {
+            content.push('\n');
+        }
+        content.push_str(line);
+        length += separator + line_length;
+        included += 1;
+    }
+
+    let omitted = lines.len() - included;
+    if omitted > 0 {
+        let plural = if omitted == 1 { "" } else { "s" };
+        content.push_str(&format!("\n…and {} more change{}", omitted, plural));
+    }
+    content
+}
+
+async fn load_state(path: &Path) -> Result<Option<Snapshot>> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(json) => serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| ScraperError::InvalidSnapshot(e.to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -1,18 +1,17 @@
 // tests/all_topics_test.rs
 
 use dotenvy::dotenv;
-use spot_scraper::{Result, SpotClient};
+use futures::StreamExt;
+use spot_scraper::{CrawlEvent, Result, SpotClient};
 use std::env;
 use std::fs::File;
 use std::io::Write;
-use std::time::Duration;
-use tokio::time::sleep;
 
 /// Integration test ini akan melakukan scraping SEMUA topik yang bisa diakses
 /// dari SEMUA mata kuliah dan menyimpan output-nya ke file `all_topics_output.log`.
 ///
-/// Test ini sengaja dibuat lambat dengan jeda antar request untuk menghormati
-/// server SPOT dan menghindari potensi rate-limiting.
+/// Jeda antar request diatur oleh rate limit bawaan `SpotClient`, jadi test ini
+/// tetap menghormati server SPOT tanpa `sleep` manual.
 ///
 /// Cara menjalankan test ini:
 /// SPOT_NIM="your_nim" SPOT_PASSWORD="your_password" cargo test test_scrape_all_topics -- --nocapture
@@ -39,118 +38,68 @@ async fn test_scrape_all_topics() -> Result<()> {
     client.login(&nim, &password).await?;
     writeln!(log_file, "[1/4] Login berhasil!").unwrap();
 
-    // --- Langkah 2: Ambil Daftar Mata Kuliah ---
-    writeln!(log_file, "\n[2/4] Mengambil daftar mata kuliah...").unwrap();
-    println!("[2/4] Mengambil daftar mata kuliah...");
-    let courses = client.get_courses().await?;
+    // --- Langkah 2-4: Crawl semua mata kuliah dan topik secara konkuren ---
     writeln!(
         log_file,
-        "[2/4] Berhasil mendapatkan {} mata kuliah.",
-        courses.len()
+        "\n[2/4] - [4/4] Memulai crawl semua mata kuliah dan topik..."
     )
     .unwrap();
-
-    // --- Langkah 3 & 4: Loop Semua Mata Kuliah dan Topiknya ---
-    writeln!(
-        log_file,
-        "\n[3/4] & [4/4] Memulai proses scraping untuk setiap mata kuliah dan topik..."
-    )
-    .unwrap();
-    println!("[3/4] & [4/4] Memulai proses scraping untuk setiap mata kuliah dan topik...");
-
-    for (course_index, course) in courses.iter().enumerate() {
-        writeln!(
-            log_file,
-            "\n=================================================="
-        )
-        .unwrap();
-        writeln!(
-            log_file,
-            "({}/{}) Scraping Course: {}",
-            course_index + 1,
-            courses.len(),
-            course.name
-        )
-        .unwrap();
-        println!(
-            "\n({}/{}) Scraping Course: {}",
-            course_index + 1,
-            courses.len(),
-            course.name
-        );
-
-        // Jeda singkat antar request mata kuliah
-        sleep(Duration::from_millis(500)).await;
-
-        match client.get_course_detail(course).await {
-            Ok(course_detail) => {
-                let accessible_topics: Vec<_> = course_detail
-                    .topics
-                    .iter()
-                    .filter(|t| t.is_accessible)
-                    .collect();
-                if accessible_topics.is_empty() {
-                    writeln!(
-                        log_file,
-                        " -> Tidak ada topik yang bisa diakses di mata kuliah ini."
-                    )
-                    .unwrap();
-                    println!(" -> Tidak ada topik yang bisa diakses di mata kuliah ini.");
-                    continue;
-                }
-
-                for (topic_index, topic_info) in accessible_topics.iter().enumerate() {
-                    writeln!(
-                        log_file,
-                        "\n--------------------------------------------------"
-                    )
-                    .unwrap();
-                    let topic_id_str = topic_info
-                        .id
-                        .map(|v| v.to_string())
-                        .unwrap_or_else(|| "N/A".to_string());
-                    writeln!(
-                        log_file,
-                        "    -> ({}/{}) Scraping Topic ID: {}",
-                        topic_index + 1,
-                        accessible_topics.len(),
-                        topic_id_str
-                    )
-                    .unwrap();
-                    println!(
-                        "    -> ({}/{}) Scraping Topic ID: {}",
-                        topic_index + 1,
-                        accessible_topics.len(),
-                        topic_id_str
-                    );
-
-                    // Jeda singkat antar request topik
-                    sleep(Duration::from_millis(250)).await;
-
-                    match client.get_topic_detail(topic_info).await {
-                        Ok(topic_detail) => {
-                            writeln!(log_file, "{:#?}", topic_detail).unwrap();
-                        }
-                        Err(e) => {
-                            writeln!(
-                                log_file,
-                                "    -> ERROR: Gagal mengambil detail topik: {:?}",
-                                e
-                            )
-                            .unwrap();
-                            println!("    -> ERROR: Gagal mengambil detail topik: {:?}", e);
-                        }
-                    }
-                }
+    println!("[2/4] - [4/4] Memulai crawl semua mata kuliah dan topik...");
+
+    let mut events = client.crawl();
+    while let Some(event) = events.next().await {
+        match event? {
+            CrawlEvent::CourseFound(course) => {
+                writeln!(log_file, "\nMata kuliah: {}", course.name).unwrap();
+                println!("Mata kuliah: {}", course.name);
+            }
+            CrawlEvent::CourseDetail(detail) => {
+                let accessible = detail.topics.iter().filter(|t| t.is_accessible).count();
+                writeln!(
+                    log_file,
+                    "\n==================================================\n{}: {} dari {} topik bisa diakses",
+                    detail.course_info.name,
+                    accessible,
+                    detail.topics.len()
+                )
+                .unwrap();
+            }
+            CrawlEvent::CourseFailed { course, error } => {
+                writeln!(
+                    log_file,
+                    " -> ERROR: Gagal mengambil detail mata kuliah {}: {:?}",
+                    course.name, error
+                )
+                .unwrap();
+                println!(
+                    " -> ERROR: Gagal mengambil detail mata kuliah {}: {:?}",
+                    course.name, error
+                );
+            }
+            CrawlEvent::Topic { course_id, topic } => {
+                writeln!(
+                    log_file,
+                    "\n--------------------------------------------------\n    -> Topik {} (mata kuliah {})\n{:#?}",
+                    topic.id, course_id, topic
+                )
+                .unwrap();
+                println!("    -> Topik {} (mata kuliah {})", topic.id, course_id);
             }
-            Err(e) => {
+            CrawlEvent::TopicFailed {
+                course_id,
+                topic,
+                error,
+            } => {
                 writeln!(
                     log_file,
-                    " -> ERROR: Gagal mengambil detail mata kuliah: {:?}",
-                    e
+                    "    -> ERROR: Gagal mengambil detail topik {:?} (mata kuliah {}): {:?}",
+                    topic.id, course_id, error
                 )
                 .unwrap();
-                println!(" -> ERROR: Gagal mengambil detail mata kuliah: {:?}", e);
+                println!(
+                    "    -> ERROR: Gagal mengambil detail topik {:?} (mata kuliah {}): {:?}",
+                    topic.id, course_id, error
+                );
             }
         }
     }
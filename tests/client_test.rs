@@ -0,0 +1,684 @@
+// tests/client_test.rs
+
+//! Offline tests of the full `SpotClient` flow against the mock SPOT and CAS
+//! servers in `tests/common`.
+
+mod common;
+
+use chrono::Utc;
+use common::{
+    COURSE_ID, FILE_BYTES, MockSpot, NIM, PASSWORD, TASK_ID, TOPIC_ID, fixture, html_with_status,
+};
+use spot_scraper::{
+    AnswerFile, AnswerSubmission, ContentBlock, Credentials, EmbedKind, Inline, LoginFailure,
+    ScraperError, SpotClient, TaskFilter, TaskStatus, TopicDetail, WITA,
+};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+/// The first topic of the first course, which carries the task.
+async fn first_topic(client: &SpotClient) -> TopicDetail {
+    let courses = client.get_courses().await.unwrap();
+    let detail = client.get_course_detail(&courses[0]).await.unwrap();
+    client.get_topic_detail(&detail.topics[0]).await.unwrap()
+}
+
+async fn logged_in() -> (MockSpot, SpotClient) {
+    let mock = MockSpot::start().await;
+    let client = mock.client();
+    client
+        .login(NIM, PASSWORD)
+        .await
+        .expect("login should succeed");
+    (mock, client)
+}
+
+#[tokio::test]
+async fn login_and_fetch_profile_and_courses() {
+    let (mock, client) = logged_in().await;
+    assert_eq!(mock.logins(), 1);
+
+    let user = client.get_user_profile().await.unwrap();
+    assert_eq!(user.name, "ANDIKA EKA KURNIA");
+    assert_eq!(user.nim, NIM);
+
+    let courses = client.get_courses().await.unwrap();
+    assert_eq!(courses.len(), 2);
+    assert_eq!(courses[0].id, COURSE_ID);
+    assert_eq!(courses[0].code, "PT502");
+    assert_eq!(courses[0].credits, 4);
+    assert_eq!(courses[0].href, format!("/mhs/dashboard/{}", COURSE_ID));
+}
+
+#[tokio::test]
+async fn login_with_wrong_password_fails() {
+    let mock = MockSpot::start().await;
+    let client = mock.client();
+
+    let result = client.login(NIM, "salah").await;
+    match result {
+        Err(ScraperError::AuthenticationFailed {
+            reason,
+            message,
+            url,
+        }) => {
+            assert_eq!(reason, LoginFailure::InvalidCredentials);
+            assert_eq!(message.as_deref(), Some("Invalid credentials."));
+            assert!(url.contains("/cas/login"), "{}", url);
+        }
+        other => panic!("expected a failed login, got {:?}", other),
+    }
+    assert_eq!(mock.logins(), 0);
+}
+
+#[tokio::test]
+async fn requests_without_session_report_expiry() {
+    let mock = MockSpot::start().await;
+    let client = mock.client();
+
+    assert!(!client.is_logged_in().await.unwrap());
+    assert!(matches!(
+        client.get_courses().await,
+        Err(ScraperError::SessionExpired)
+    ));
+
+    client.login(NIM, PASSWORD).await.unwrap();
+    assert!(client.is_logged_in().await.unwrap());
+
+    mock.expire_sessions();
+    assert!(!client.is_logged_in().await.unwrap());
+    assert!(matches!(
+        client.get_user_profile().await,
+        Err(ScraperError::SessionExpired)
+    ));
+}
+
+#[tokio::test]
+async fn course_and_topic_details_are_parsed() {
+    let (_mock, client) = logged_in().await;
+    let courses = client.get_courses().await.unwrap();
+
+    let detail = client.get_course_detail(&courses[0]).await.unwrap();
+    assert!(detail.description.starts_with("Pembelajaran perkuliahan"));
+    assert_eq!(detail.rps.id, Some(COURSE_ID));
+    assert_eq!(detail.topics.len(), 3);
+    assert_eq!(detail.topics[0].id, Some(TOPIC_ID));
+    assert_eq!(detail.topics[0].course_id, Some(COURSE_ID));
+    assert!(!detail.topics[2].is_accessible);
+    assert!(detail.topics[2].access_time.is_some());
+
+    let unset = client.get_course_detail(&courses[1]).await.unwrap();
+    assert!(unset.topics.is_empty());
+
+    let topic = client.get_topic_detail(&detail.topics[0]).await.unwrap();
+    assert_eq!(topic.id, TOPIC_ID);
+    assert_eq!(topic.contents.len(), 3);
+    assert_eq!(topic.contents[1].youtube_id.as_deref(), Some("dQw4w9WgXcQ"));
+
+    let task = &topic.tasks[0];
+    assert_eq!(task.id, Some(TASK_ID));
+    assert_eq!(task.title, "Proposal Mitra");
+    assert_eq!(task.token, "mock-csrf-token");
+    assert!(task.due_date.is_some());
+    assert!(task.answer.is_none());
+    assert_eq!(task.status(), TaskStatus::Pending);
+}
+
+#[tokio::test]
+async fn dates_carry_the_source_zone() {
+    let (mock, client) = logged_in().await;
+    let courses = client.get_courses().await.unwrap();
+    let detail = client.get_course_detail(&courses[0]).await.unwrap();
+    let topic = client.get_topic_detail(&detail.topics[0]).await.unwrap();
+    // 31-12-2099 23:59 WIB.
+    let due = topic.tasks[0].due_date.unwrap();
+    assert_eq!(due.to_rfc3339(), "2099-12-31T23:59:00+07:00");
+
+    let client = mock.builder().timezone(WITA).build().unwrap();
+    client.login(NIM, PASSWORD).await.unwrap();
+    let topic = client.get_topic_detail(&detail.topics[0]).await.unwrap();
+    let due = topic.tasks[0].due_date.unwrap();
+    assert_eq!(due.to_rfc3339(), "2099-12-31T23:59:00+08:00");
+}
+
+#[tokio::test]
+async fn rps_is_parsed() {
+    let (_mock, client) = logged_in().await;
+    let courses = client.get_courses().await.unwrap();
+    let detail = client.get_course_detail(&courses[0]).await.unwrap();
+
+    let rps = client.get_rps(&detail.rps).await.unwrap();
+    assert_eq!(rps.rps.id, Some(COURSE_ID));
+
+    assert_eq!(rps.learning_outcomes.len(), 3);
+    assert_eq!(rps.learning_outcomes[1].code.as_deref(), Some("CPMK-1"));
+    assert_eq!(
+        rps.learning_outcomes[2].description,
+        "Mahasiswa mampu menyusun proposal solusi teknologi tepat guna."
+    );
+
+    let weeks: Vec<Vec<u32>> = rps.weekly_plan.iter().map(|w| w.weeks.clone()).collect();
+    assert_eq!(weeks, vec![vec![1], vec![2, 3], vec![8], vec![9, 10]]);
+    let second = &rps.weekly_plan[1];
+    assert_eq!(second.outcome, "Mengidentifikasi calon mitra");
+    assert_eq!(second.material, "Analisis kebutuhan\nWawancara mitra");
+    assert_eq!(second.method, "Project based learning");
+    assert_eq!(second.assessment, "Proposal mitra");
+    assert_eq!(second.weight, Some(15.0));
+    assert_eq!(rps.weekly_plan[2].outcome, "Ujian Tengah Semester");
+    assert_eq!(rps.weekly_plan[2].weight, Some(25.0));
+    assert_eq!(rps.weekly_plan[3].weight, None);
+
+    let weights: Vec<(&str, f32)> = rps
+        .assessments
+        .iter()
+        .map(|a| (a.component.as_str(), a.weight))
+        .collect();
+    assert_eq!(
+        weights,
+        vec![
+            ("Tugas", 30.0),
+            ("UTS", 25.0),
+            ("UAS", 35.0),
+            ("Kehadiran", 10.5)
+        ]
+    );
+
+    assert_eq!(rps.references.len(), 2);
+    assert!(rps.references[0].starts_with("Pressman, R. S. (2014). Software Engineering"));
+}
+
+#[tokio::test]
+async fn rps_ignores_absurd_weeks_and_spans() {
+    let (mock, client) = logged_in().await;
+    let courses = client.get_courses().await.unwrap();
+    let detail = client.get_course_detail(&courses[0]).await.unwrap();
+
+    let page = fixture("rps.html")
+        .replace("<td>2-3</td>", "<td>1-40000000</td>")
+        .replace("<td>9, 10</td>", "<td>9, 99999</td>")
+        .replace(r#"colspan="4""#, r#"colspan="400000000""#);
+    Mock::given(method("GET"))
+        .and(path(format!("/mhs/rps/{}", COURSE_ID)))
+        .respond_with(html_with_status(200, page))
+        .with_priority(1)
+        .mount(&mock.spot)
+        .await;
+
+    let rps = client.get_rps(&detail.rps).await.unwrap();
+    let weeks: Vec<Vec<u32>> = rps.weekly_plan.iter().map(|w| w.weeks.clone()).collect();
+    // The row for "1-40000000" has no usable week left and is dropped.
+    assert_eq!(weeks, vec![vec![1], vec![8], vec![9]]);
+    assert_eq!(rps.weekly_plan[1].outcome, "Ujian Tengah Semester");
+    // The clamped span still covers the weight column.
+    assert_eq!(rps.weekly_plan[1].weight, None);
+}
+
+#[tokio::test]
+async fn materials_are_parsed_into_blocks() {
+    let (mock, client) = logged_in().await;
+    let courses = client.get_courses().await.unwrap();
+    let detail = client.get_course_detail(&courses[0]).await.unwrap();
+    let topic = client.get_topic_detail(&detail.topics[0]).await.unwrap();
+    let base = mock.spot.uri();
+
+    assert_eq!(
+        topic.contents[0].blocks,
+        vec![
+            ContentBlock::Heading {
+                level: 4,
+                inlines: vec![text("Buku Panduan Proyek Konsultansi")],
+            },
+            ContentBlock::Paragraph {
+                inlines: vec![text("Sumber tim Proyek Konsultansi UPI")],
+            },
+            ContentBlock::File {
+                name: "Download File Materi".to_string(),
+                url: format!("{}/mhs/download/2510009533/1370508/163205", base),
+            },
+        ]
+    );
+    assert_eq!(
+        topic.contents[1].blocks[1],
+        ContentBlock::Video {
+            url: "https://www.youtube.com/embed/dQw4w9WgXcQ?rel=0".to_string(),
+            youtube_id: Some("dQw4w9WgXcQ".to_string()),
+        }
+    );
+
+    assert_eq!(
+        topic.contents[2]
+            .embeds
+            .iter()
+            .map(|e| &e.kind)
+            .collect::<Vec<_>>(),
+        vec![
+            &EmbedKind::DirectFile {
+                extension: Some("docx".to_string())
+            },
+            &EmbedKind::GoogleForms {
+                form_id: "abc123".to_string()
+            },
+        ]
+    );
+
+    let blocks = &topic.contents[2].blocks;
+    assert_eq!(
+        blocks[1],
+        ContentBlock::Paragraph {
+            inlines: vec![
+                text("Baca "),
+                Inline::Strong {
+                    text: "panduan".to_string()
+                },
+                text(" lalu isi "),
+                Inline::Link {
+                    text: "formulir mitra".to_string(),
+                    url: format!("{}/uploads/materi/form%20mitra.docx", base),
+                },
+                text("."),
+                Inline::LineBreak,
+                text("Kirim via "),
+                Inline::Link {
+                    text: "Google Form".to_string(),
+                    url: "https://forms.gle/abc123".to_string(),
+                },
+                text("."),
+            ],
+        }
+    );
+    // The file link stays in the sentence but is still listed as an embed.
+    assert!(topic.contents[2].embeds.iter().any(|embed| {
+        matches!(embed.kind, EmbedKind::DirectFile { .. })
+            && embed.url.ends_with("form%20mitra.docx")
+    }));
+    let ContentBlock::List { ordered, items } = &blocks[2] else {
+        panic!("expected a list, got {:?}", blocks[2]);
+    };
+    assert!(ordered);
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].inlines, vec![text("Cari calon mitra")]);
+    assert!(matches!(
+        &items[0].children[0],
+        ContentBlock::List { ordered: false, items } if items.len() == 2
+    ));
+    assert_eq!(
+        blocks[3],
+        ContentBlock::Image {
+            src: format!("{}/images/alur.png", base),
+            alt: Some("Alur kerja".to_string()),
+        }
+    );
+    assert_eq!(
+        blocks[4],
+        ContentBlock::Code {
+            language: Some("bash".to_string()),
+            code: "git clone repo\ncd repo".to_string(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn materials_render_as_markdown_and_plain_text() {
+    let (mock, client) = logged_in().await;
+    mock.set_answered(true);
+    mock.set_graded(true);
+    let courses = client.get_courses().await.unwrap();
+    let detail = client.get_course_detail(&courses[0]).await.unwrap();
+    let topic = client.get_topic_detail(&detail.topics[0]).await.unwrap();
+    let base = mock.spot.uri();
+
+    assert_eq!(
+        topic.contents[2].to_markdown(),
+        format!(
+            "#### Langkah Kerja\n\n\
+             Baca **panduan** lalu isi \
+             [formulir mitra]({base}/uploads/materi/form%20mitra.docx).\\\nKirim via [Google Form](https://forms.gle/abc123).\n\n\
+             1. Cari calon mitra\n   - UMKM sekitar kampus\n   - Instansi pemerintah\n\
+             2. Susun proposal\n\n\
+             ![Alur kerja]({base}/images/alur.png)\n\n\
+             ```bash\ngit clone repo\ncd repo\n```"
+        )
+    );
+    assert_eq!(
+        topic.contents[0].to_plain_text(),
+        format!(
+            "Buku Panduan Proyek Konsultansi\n\n\
+             Sumber tim Proyek Konsultansi UPI\n\n\
+             Download File Materi ({base}/mhs/download/2510009533/1370508/163205)"
+        )
+    );
+
+    let task = &topic.tasks[0];
+    assert_eq!(
+        task.description_markdown(),
+        "Unggah proposal kerjasama dengan **calon mitra**."
+    );
+    assert_eq!(
+        task.description_plain_text(),
+        "Unggah proposal kerjasama dengan calon mitra."
+    );
+    let answer = task.answer.as_ref().unwrap();
+    assert_eq!(
+        answer.lecturer_notes_plain_text(),
+        "Proposal sudah baik.\nLihat contoh (https://example.com/contoh)."
+    );
+}
+
+fn text(text: &str) -> Inline {
+    Inline::Text {
+        text: text.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn submit_and_replace_task_answer() {
+    let (mock, client) = logged_in().await;
+    let courses = client.get_courses().await.unwrap();
+    let detail = client.get_course_detail(&courses[0]).await.unwrap();
+    let topic = client.get_topic_detail(&detail.topics[0]).await.unwrap();
+
+    let submission = AnswerSubmission::text("Proposal kerjasama terlampir.").with_file(
+        AnswerFile::new("proposal.pdf", b"proposal".to_vec()).mime_type("application/pdf"),
+    );
+    let answer = client
+        .submit_task_answer(&topic.tasks[0], submission)
+        .await
+        .unwrap();
+    assert_eq!(answer.id, Some(55123));
+    assert!(!answer.is_graded);
+
+    let body = mock.last_submission().unwrap();
+    assert!(body.contains("name=\"deskripsi\""));
+    assert!(body.contains("Proposal kerjasama terlampir."));
+    assert!(body.contains("name=\"file_tugas\"; filename=\"proposal.pdf\""));
+    assert!(body.contains(&TASK_ID.to_string()));
+
+    let topic = client.get_topic_detail(&detail.topics[0]).await.unwrap();
+    let replaced = client
+        .replace_answer(&topic.tasks[0], AnswerSubmission::text("Revisi proposal."))
+        .await
+        .unwrap();
+    assert_eq!(replaced.id, Some(55123));
+    assert!(mock.last_submission().unwrap().contains("Revisi proposal."));
+}
+
+#[tokio::test]
+async fn graded_answers_cannot_be_replaced() {
+    let (mock, client) = logged_in().await;
+    mock.set_answered(true);
+    mock.set_graded(true);
+
+    let courses = client.get_courses().await.unwrap();
+    let detail = client.get_course_detail(&courses[0]).await.unwrap();
+    let topic = client.get_topic_detail(&detail.topics[0]).await.unwrap();
+    let task = &topic.tasks[0];
+    assert_eq!(task.status(), TaskStatus::Graded);
+    assert_eq!(task.answer.as_ref().unwrap().score, 87.5);
+
+    let result = client
+        .replace_answer(task, AnswerSubmission::text("Terlambat"))
+        .await;
+    assert!(matches!(result, Err(ScraperError::AnswerGraded)));
+    assert!(mock.last_submission().is_none());
+}
+
+#[tokio::test]
+async fn closed_answers_are_rejected_without_any_request() {
+    let (mock, client) = logged_in().await;
+    mock.set_answered(true);
+    let topic = first_topic(&client).await;
+    let requests = || async { mock.spot.received_requests().await.unwrap().len() };
+    let before = requests().await;
+
+    let mut overdue = topic.tasks[0].clone();
+    overdue.due_date = Some(Utc::now().fixed_offset() - chrono::Duration::minutes(1));
+    assert!(matches!(
+        client.delete_answer(&overdue).await,
+        Err(ScraperError::DeadlinePassed(_))
+    ));
+    assert!(matches!(
+        client
+            .replace_answer(&overdue, AnswerSubmission::text("Terlambat"))
+            .await,
+        Err(ScraperError::DeadlinePassed(_))
+    ));
+
+    let mut graded = topic.tasks[0].clone();
+    graded.answer.as_mut().unwrap().is_graded = true;
+    assert!(matches!(
+        client.delete_answer(&graded).await,
+        Err(ScraperError::AnswerGraded)
+    ));
+    assert!(matches!(
+        client
+            .replace_answer(&graded, AnswerSubmission::text("Revisi"))
+            .await,
+        Err(ScraperError::AnswerGraded)
+    ));
+
+    assert_eq!(requests().await, before);
+}
+
+#[tokio::test]
+async fn empty_answers_are_rejected_without_any_request() {
+    let (mock, client) = logged_in().await;
+    let topic = first_topic(&client).await;
+    let task = &topic.tasks[0];
+    let requests = || async { mock.spot.received_requests().await.unwrap().len() };
+    let before = requests().await;
+
+    for submission in [AnswerSubmission::text(""), AnswerSubmission::text("  \n")] {
+        assert!(matches!(
+            client.submit_task_answer(task, submission).await,
+            Err(ScraperError::SubmissionFailed(message)) if message == "empty answer"
+        ));
+    }
+    mock.set_answered(true);
+    let answered = client.get_topic_detail(&task.topic_info()).await.unwrap();
+    let before_replace = requests().await;
+    assert!(matches!(
+        client
+            .replace_answer(&answered.tasks[0], AnswerSubmission::text(""))
+            .await,
+        Err(ScraperError::SubmissionFailed(_))
+    ));
+
+    // Only the topic page was fetched; no answer was posted or deleted.
+    assert_eq!(before_replace - before, 1);
+    assert_eq!(requests().await, before_replace);
+}
+
+#[tokio::test]
+async fn delete_answer_checks_that_the_answer_is_gone() {
+    let (mock, client) = logged_in().await;
+    mock.set_answered(true);
+    let topic = first_topic(&client).await;
+
+    client.delete_answer(&topic.tasks[0]).await.unwrap();
+    let topic = client
+        .get_topic_detail(&topic.tasks[0].topic_info())
+        .await
+        .unwrap();
+    assert!(topic.tasks[0].answer.is_none());
+}
+
+#[tokio::test]
+async fn a_failed_submission_after_deleting_is_reported() {
+    let (mock, client) = logged_in().await;
+    mock.set_answered(true);
+    Mock::given(method("POST"))
+        .and(path("/mhs/tugas"))
+        .respond_with(ResponseTemplate::new(500))
+        .with_priority(1)
+        .mount(&mock.spot)
+        .await;
+    let topic = first_topic(&client).await;
+
+    let result = client
+        .replace_answer(&topic.tasks[0], AnswerSubmission::text("Revisi proposal."))
+        .await;
+    match result {
+        Err(ScraperError::ReplacementFailed(cause)) => {
+            assert_eq!(
+                cause.status(),
+                Some(reqwest::StatusCode::INTERNAL_SERVER_ERROR)
+            );
+        }
+        other => panic!("expected a failed replacement, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn all_tasks_carry_course_and_topic_context() {
+    let (mock, client) = logged_in().await;
+
+    let tasks = client.get_all_tasks().await.unwrap();
+    // Both accessible topics of the configured course serve the same task page.
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].course.id, COURSE_ID);
+    assert_eq!(tasks[0].course.code, "PT502");
+    assert_eq!(tasks[0].topic.id, Some(TOPIC_ID));
+    assert_eq!(tasks[0].task.title, "Proposal Mitra");
+    // `tasks --json` prints these, so the status must be in the JSON.
+    let json = serde_json::to_value(&tasks[0]).unwrap();
+    assert_eq!(json["task"]["status"], "Pending");
+
+    let pending = client.get_tasks(&TaskFilter::pending()).await.unwrap();
+    assert_eq!(pending.len(), 2);
+
+    mock.set_answered(true);
+    mock.set_graded(true);
+    let graded = TaskFilter::new().status(TaskStatus::Graded);
+    assert_eq!(client.get_tasks(&graded).await.unwrap().len(), 2);
+    assert!(
+        client
+            .get_tasks(&TaskFilter::pending())
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn download_task_file() {
+    let (_mock, client) = logged_in().await;
+    let courses = client.get_courses().await.unwrap();
+    let detail = client.get_course_detail(&courses[0]).await.unwrap();
+    let topic = client.get_topic_detail(&detail.topics[0]).await.unwrap();
+    let href = topic.tasks[0].file.as_deref().unwrap();
+
+    let mut buffer = Vec::new();
+    let download = client.download(href, &mut buffer).await.unwrap();
+    assert_eq!(buffer, FILE_BYTES);
+    assert_eq!(download.size, FILE_BYTES.len() as u64);
+    assert_eq!(download.content_type.as_deref(), Some("application/pdf"));
+    assert_eq!(download.file_name.as_deref(), Some("Template Proposal.pdf"));
+
+    let dir = std::env::temp_dir().join(format!("spot-download-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (_, path) = client.download_to_path(href, &dir).await.unwrap();
+    assert_eq!(path, dir.join("Template Proposal.pdf"));
+    assert_eq!(std::fs::read(&path).unwrap(), FILE_BYTES);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn failed_downloads_leave_no_partial_file() {
+    let (mock, client) = logged_in().await;
+    // The body ends long before the announced length, so reading it fails.
+    Mock::given(method("GET"))
+        .and(path("/mhs/file_tugas/broken.pdf"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-length", "100000")
+                .set_body_bytes(FILE_BYTES),
+        )
+        .with_priority(1)
+        .mount(&mock.spot)
+        .await;
+
+    let dir = std::env::temp_dir().join(format!("spot-partial-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("broken.pdf");
+    std::fs::write(&path, "old").unwrap();
+
+    assert!(
+        client
+            .download_to_path("/mhs/file_tugas/broken.pdf", &path)
+            .await
+            .is_err()
+    );
+    assert_eq!(std::fs::read(&path).unwrap(), b"old");
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn exported_session_restores_login() {
+    let (mock, client) = logged_in().await;
+    let session = client.export_session().unwrap();
+
+    let restored = mock.builder().session(session).build().unwrap();
+    assert!(restored.is_logged_in().await.unwrap());
+    assert_eq!(restored.get_user_profile().await.unwrap().nim, NIM);
+    assert_eq!(mock.logins(), 1);
+}
+
+#[tokio::test]
+async fn saved_sessions_are_private_and_restorable() {
+    let (mock, client) = logged_in().await;
+    let path = std::env::temp_dir().join(format!("spot-session-{}.json", std::process::id()));
+    std::fs::write(&path, "stale").unwrap();
+
+    client.save_session(&path).await.unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let restored = mock.builder().session_file(&path).unwrap().build().unwrap();
+    assert!(restored.is_logged_in().await.unwrap());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn expired_session_is_renewed_with_credentials() {
+    let mock = MockSpot::start().await;
+    let client = mock
+        .builder()
+        .credentials(Credentials::new(NIM, PASSWORD))
+        .build()
+        .unwrap();
+
+    assert_eq!(client.get_user_profile().await.unwrap().nim, NIM);
+    assert_eq!(mock.logins(), 1);
+
+    mock.expire_sessions();
+    assert_eq!(client.get_courses().await.unwrap().len(), 2);
+    assert_eq!(mock.logins(), 2);
+}
+
+#[tokio::test]
+async fn reauthentication_is_capped() {
+    let mock = MockSpot::start().await;
+    let client = mock
+        .builder()
+        .credentials(Credentials::new(NIM, "salah"))
+        .max_reauth_attempts(2)
+        .build()
+        .unwrap();
+
+    for _ in 0..2 {
+        assert!(matches!(
+            client.get_courses().await,
+            Err(ScraperError::AuthenticationFailed { .. })
+        ));
+    }
+    assert!(matches!(
+        client.get_courses().await,
+        Err(ScraperError::SessionExpired)
+    ));
+}
@@ -0,0 +1,302 @@
+// tests/common/mod.rs
+
+//! A local stand-in for SPOT and the UPI CAS server, serving the recorded pages
+//! in `tests/fixtures` so the whole `SpotClient` flow can run offline.
+
+#![allow(dead_code)]
+
+use spot_scraper::{SpotClient, SpotClientBuilder};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use wiremock::matchers::{method, path, path_regex, query_param};
+use wiremock::{Match, Mock, MockBuilder, MockServer, Request, Respond, ResponseTemplate};
+
+pub const NIM: &str = "2306033";
+pub const PASSWORD: &str = "rahasia";
+pub const COURSE_ID: u64 = 2510009533;
+pub const TOPIC_ID: u64 = 1370508;
+pub const TASK_ID: u64 = 88001;
+pub const ANSWER_ID: u64 = 55123;
+pub const FILE_BYTES: &[u8] = b"%PDF-1.4 mock template";
+
+const SESSION_COOKIE: &str = "spot_session";
+
+pub fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("missing fixture {}: {}", path, e))
+}
+
+/// Shared state of the mock servers.
+#[derive(Default)]
+pub struct MockState {
+    /// The only session id SPOT currently accepts.
+    session: AtomicU64,
+    logins: AtomicUsize,
+    answered: AtomicBool,
+    graded: AtomicBool,
+    /// The last body posted to the answer upload form.
+    last_submission: Mutex<Option<String>>,
+}
+
+pub struct MockSpot {
+    pub spot: MockServer,
+    pub cas: MockServer,
+    pub state: Arc<MockState>,
+}
+
+impl MockSpot {
+    pub async fn start() -> Self {
+        let spot = MockServer::start().await;
+        let cas = MockServer::start().await;
+        let state = Arc::new(MockState::default());
+        state.session.store(1, Ordering::SeqCst);
+
+        mount_cas(&cas, &spot, &state).await;
+        mount_spot(&spot, &state).await;
+
+        Self { spot, cas, state }
+    }
+
+    pub fn builder(&self) -> SpotClientBuilder {
+        SpotClient::builder()
+            .base_url(self.spot.uri())
+            .sso_login_url(format!("{}/cas/login", self.cas.uri()))
+    }
+
+    pub fn client(&self) -> SpotClient {
+        self.builder().build().expect("mock client should build")
+    }
+
+    /// Invalidates every issued session cookie, like SPOT does after a timeout.
+    pub fn expire_sessions(&self) {
+        self.state.session.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn logins(&self) -> usize {
+        self.state.logins.load(Ordering::SeqCst)
+    }
+
+    pub fn set_answered(&self, answered: bool) {
+        self.state.answered.store(answered, Ordering::SeqCst);
+    }
+
+    pub fn set_graded(&self, graded: bool) {
+        self.state.graded.store(graded, Ordering::SeqCst);
+    }
+
+    pub fn last_submission(&self) -> Option<String> {
+        self.state.last_submission.lock().unwrap().clone()
+    }
+}
+
+async fn mount_cas(cas: &MockServer, spot: &MockServer, state: &Arc<MockState>) {
+    Mock::given(method("GET"))
+        .and(path("/cas/login"))
+        .respond_with(html(fixture("cas_login.html")))
+        .mount(cas)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/cas/login"))
+        .respond_with(CasLogin {
+            service: format!("{}/beranda", spot.uri()),
+            state: Arc::clone(state),
+        })
+        .mount(cas)
+        .await;
+}
+
+async fn mount_spot(spot: &MockServer, state: &Arc<MockState>) {
+    // The CAS service callback hands out a fresh session cookie.
+    Mock::given(method("GET"))
+        .and(path("/beranda"))
+        .and(query_param("ticket", "ST-mock-ticket"))
+        .respond_with(ServiceTicket(Arc::clone(state)))
+        .mount(spot)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(html(fixture("landing.html")))
+        .mount(spot)
+        .await;
+
+    let authed = |m: MockBuilder| m.and(HasSession(Arc::clone(state)));
+
+    authed(Mock::given(method("GET")).and(path("/mhs")))
+        .respond_with(html(fixture("mhs.html")))
+        .mount(spot)
+        .await;
+
+    authed(Mock::given(method("GET")).and(path(format!("/mhs/dashboard/{}", COURSE_ID))))
+        .respond_with(html(fixture("course_detail.html")))
+        .mount(spot)
+        .await;
+
+    authed(Mock::given(method("GET")).and(path_regex(r"^/mhs/dashboard/\d+$")))
+        .respond_with(html(fixture("course_unset.html")))
+        .with_priority(6)
+        .mount(spot)
+        .await;
+
+    authed(Mock::given(method("GET")).and(path(format!("/mhs/rps/{}", COURSE_ID))))
+        .respond_with(html(fixture("rps.html")))
+        .mount(spot)
+        .await;
+
+    authed(Mock::given(method("GET")).and(path_regex(r"^/mhs/topik/\d+/\d+$")))
+        .respond_with(TopicPage(Arc::clone(state)))
+        .mount(spot)
+        .await;
+
+    authed(Mock::given(method("POST")).and(path("/mhs/tugas")))
+        .respond_with(SubmitAnswer(Arc::clone(state)))
+        .mount(spot)
+        .await;
+
+    authed(Mock::given(method("GET")).and(path_regex(r"^/mhs/tugas_del/")))
+        .respond_with(DeleteAnswer(Arc::clone(state)))
+        .mount(spot)
+        .await;
+
+    authed(Mock::given(method("GET")).and(path_regex(r"^/mhs/file_tugas/")))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "application/pdf")
+                .insert_header(
+                    "content-disposition",
+                    "attachment; filename=\"template.pdf\"; filename*=UTF-8''Template%20Proposal.pdf",
+                )
+                .set_body_bytes(FILE_BYTES),
+        )
+        .mount(spot)
+        .await;
+
+    // Without a valid session SPOT bounces every student page to the landing page.
+    Mock::given(method("GET"))
+        .and(path_regex(r"^/mhs"))
+        .respond_with(redirect("/"))
+        .with_priority(10)
+        .mount(spot)
+        .await;
+}
+
+fn html(body: String) -> ResponseTemplate {
+    html_with_status(200, body)
+}
+
+pub fn html_with_status(status: u16, body: String) -> ResponseTemplate {
+    ResponseTemplate::new(status)
+        .insert_header("content-type", "text/html; charset=UTF-8")
+        .set_body_string(body)
+}
+
+fn redirect(location: &str) -> ResponseTemplate {
+    ResponseTemplate::new(302).insert_header("location", location)
+}
+
+/// Matches requests carrying the session cookie SPOT currently accepts.
+struct HasSession(Arc<MockState>);
+
+impl Match for HasSession {
+    fn matches(&self, request: &Request) -> bool {
+        let expected = format!(
+            "{}=session-{}",
+            SESSION_COOKIE,
+            self.0.session.load(Ordering::SeqCst)
+        );
+        request
+            .headers
+            .get_all("cookie")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+            .any(|cookie| cookie.trim() == expected)
+    }
+}
+
+struct CasLogin {
+    service: String,
+    state: Arc<MockState>,
+}
+
+impl Respond for CasLogin {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let form: Vec<(String, String)> = url::form_urlencoded::parse(&request.body)
+            .into_owned()
+            .collect();
+        let field = |name: &str| {
+            form.iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        };
+
+        if field("execution") != Some("e1s1-mock-execution-token") {
+            return html_with_status(400, fixture("cas_login.html"));
+        }
+        if field("username") == Some(NIM) && field("password") == Some(PASSWORD) {
+            self.state.logins.fetch_add(1, Ordering::SeqCst);
+            return redirect(&format!("{}?ticket=ST-mock-ticket", self.service));
+        }
+
+        let page = fixture("cas_login.html").replace(
+            "<!-- ERROR -->",
+            "<div id=\"msg\" class=\"errors\">Invalid credentials.</div>",
+        );
+        html_with_status(401, page)
+    }
+}
+
+struct ServiceTicket(Arc<MockState>);
+
+impl Respond for ServiceTicket {
+    fn respond(&self, _request: &Request) -> ResponseTemplate {
+        let session = self.0.session.load(Ordering::SeqCst);
+        redirect("/mhs").insert_header(
+            "set-cookie",
+            format!("{}=session-{}; Path=/; HttpOnly", SESSION_COOKIE, session).as_str(),
+        )
+    }
+}
+
+struct TopicPage(Arc<MockState>);
+
+impl Respond for TopicPage {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let mut page = fixture("topic.html");
+        if self.0.answered.load(Ordering::SeqCst) {
+            let mut answer = fixture("topic_answer.html");
+            if self.0.graded.load(Ordering::SeqCst) {
+                answer = answer.replace("<!-- GRADE -->", &fixture("topic_grade.html"));
+            }
+            page = page.replace("<!-- ANSWER -->", &answer);
+        }
+        // Other topics serve the same page, but with their own task and answer ids.
+        if !request.url.path().ends_with(&format!("/{}", TOPIC_ID)) {
+            page = page
+                .replace(&TASK_ID.to_string(), &(TASK_ID + 1).to_string())
+                .replace(&ANSWER_ID.to_string(), &(ANSWER_ID + 1).to_string());
+        }
+        html(page)
+    }
+}
+
+struct SubmitAnswer(Arc<MockState>);
+
+impl Respond for SubmitAnswer {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body = String::from_utf8_lossy(&request.body).into_owned();
+        *self.0.last_submission.lock().unwrap() = Some(body);
+        self.0.answered.store(true, Ordering::SeqCst);
+        redirect(&format!("/mhs/topik/{}/{}", COURSE_ID, TOPIC_ID))
+    }
+}
+
+struct DeleteAnswer(Arc<MockState>);
+
+impl Respond for DeleteAnswer {
+    fn respond(&self, _request: &Request) -> ResponseTemplate {
+        self.0.answered.store(false, Ordering::SeqCst);
+        redirect(&format!("/mhs/topik/{}/{}", COURSE_ID, TOPIC_ID))
+    }
+}
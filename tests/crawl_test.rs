@@ -0,0 +1,99 @@
+// tests/crawl_test.rs
+
+//! Tests of the concurrent crawl against the mock SPOT.
+
+mod common;
+
+use common::{COURSE_ID, MockSpot, NIM, PASSWORD, TOPIC_ID};
+use futures::StreamExt;
+use reqwest::StatusCode;
+use spot_scraper::{CrawlEvent, RateLimit, RetryPolicy, ScraperError, SpotClient};
+use wiremock::matchers::path;
+use wiremock::{Mock, ResponseTemplate};
+
+async fn crawl(client: &SpotClient) -> Vec<spot_scraper::Result<CrawlEvent>> {
+    client.crawl().collect().await
+}
+
+fn fast_client(mock: &MockSpot) -> SpotClient {
+    mock.builder()
+        .rate_limit(RateLimit::unlimited())
+        .retry(RetryPolicy::none())
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn every_course_and_accessible_topic_is_crawled() {
+    let mock = MockSpot::start().await;
+    let client = fast_client(&mock);
+    client.login(NIM, PASSWORD).await.unwrap();
+
+    let events: Vec<CrawlEvent> = crawl(&client)
+        .await
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+
+    // Courses are all announced before anything else.
+    assert!(matches!(events[0], CrawlEvent::CourseFound(_)));
+    assert!(matches!(events[1], CrawlEvent::CourseFound(_)));
+    let count = |f: fn(&CrawlEvent) -> bool| events.iter().filter(|e| f(e)).count();
+    assert_eq!(count(|e| matches!(e, CrawlEvent::CourseFound(_))), 2);
+    assert_eq!(count(|e| matches!(e, CrawlEvent::CourseDetail(_))), 2);
+    // The third topic is locked and not fetched.
+    assert_eq!(count(|e| matches!(e, CrawlEvent::Topic { .. })), 2);
+    assert_eq!(events.len(), 6);
+    assert!(events.iter().any(|e| match e {
+        CrawlEvent::Topic { course_id, topic } => *course_id == COURSE_ID && topic.id == TOPIC_ID,
+        _ => false,
+    }));
+}
+
+#[tokio::test]
+async fn a_broken_topic_does_not_stop_the_crawl() {
+    let mock = MockSpot::start().await;
+    Mock::given(path(format!("/mhs/topik/{}/{}", COURSE_ID, TOPIC_ID + 1)))
+        .respond_with(ResponseTemplate::new(500))
+        .with_priority(1)
+        .mount(&mock.spot)
+        .await;
+    let client = fast_client(&mock);
+    client.login(NIM, PASSWORD).await.unwrap();
+
+    let events = crawl(&client).await;
+    assert_eq!(events.len(), 6);
+    let failed: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            Ok(CrawlEvent::TopicFailed {
+                course_id,
+                topic,
+                error,
+            }) => Some((*course_id, topic.id, error.status())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        failed,
+        vec![(
+            COURSE_ID,
+            Some(TOPIC_ID + 1),
+            Some(StatusCode::INTERNAL_SERVER_ERROR)
+        )]
+    );
+    assert!(events.iter().any(|e| match e {
+        Ok(CrawlEvent::Topic { topic, .. }) => topic.id == TOPIC_ID,
+        _ => false,
+    }));
+}
+
+#[tokio::test]
+async fn failing_to_list_courses_ends_the_crawl() {
+    let mock = MockSpot::start().await;
+    let client = fast_client(&mock);
+
+    let events = crawl(&client).await;
+    assert_eq!(events.len(), 1);
+    assert!(matches!(events[0], Err(ScraperError::SessionExpired)));
+}
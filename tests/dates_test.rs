@@ -0,0 +1,87 @@
+// tests/dates_test.rs
+
+use chrono::{DateTime, FixedOffset, TimeZone};
+use spot_scraper::{WIB, WIT, WITA, parse_datetime};
+
+fn wib(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<FixedOffset> {
+    WIB.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+}
+
+#[test]
+fn numeric_formats() {
+    let expected = wib(2025, 9, 4, 7, 0);
+    for text in [
+        "04-09-2025 07:00",
+        "04/09/2025 07:00:00",
+        "2025-09-04 07:00",
+        "  Waktu Akses: 04-09-2025 07:00 ",
+    ] {
+        assert_eq!(parse_datetime(text, WIB), Some(expected), "{}", text);
+    }
+    assert_eq!(
+        parse_datetime("04-09-2025 07:00:30", WIB),
+        Some(WIB.with_ymd_and_hms(2025, 9, 4, 7, 0, 30).unwrap())
+    );
+}
+
+#[test]
+fn indonesian_month_names() {
+    assert_eq!(
+        parse_datetime("Kamis, 4 September 2025 pukul 07.00", WIB),
+        Some(wib(2025, 9, 4, 7, 0))
+    );
+    assert_eq!(
+        parse_datetime("18 Desember 2025 23:59", WIB),
+        Some(wib(2025, 12, 18, 23, 59))
+    );
+    assert_eq!(
+        parse_datetime("1 Agt 2025, 08:15", WIB),
+        Some(wib(2025, 8, 1, 8, 15))
+    );
+    assert_eq!(
+        parse_datetime("17 Mei 2025", WIB),
+        Some(wib(2025, 5, 17, 0, 0))
+    );
+}
+
+#[test]
+fn zone_suffix_overrides_the_default() {
+    let wita = parse_datetime("4 Oktober 2025 09:00 WITA", WIB).unwrap();
+    assert_eq!(wita.offset(), &WITA);
+    assert_eq!(wita, wib(2025, 10, 4, 8, 0));
+
+    let wit = parse_datetime("04-10-2025 09:00", WIT).unwrap();
+    assert_eq!(wit, wib(2025, 10, 4, 7, 0));
+}
+
+#[test]
+fn invalid_dates_are_rejected() {
+    for text in [
+        "",
+        "belum ditentukan",
+        "31-02-2025 07:00",
+        "04-09-2025 25:00",
+        "04-09 07:00",
+    ] {
+        assert_eq!(parse_datetime(text, WIB), None, "{}", text);
+    }
+}
+
+#[test]
+fn two_digit_years_are_rejected() {
+    assert_eq!(parse_datetime("04-09-25 07:00", WIB), None);
+    assert_eq!(parse_datetime("25-09-04 07:00", WIB), None);
+    assert_eq!(parse_datetime("4 September 25", WIB), None);
+}
+
+#[test]
+fn extra_numbers_are_rejected() {
+    for text in [
+        "1 04-09-2025 07:00",
+        "3 2025-09-04 07:00",
+        "04-09-2025 07:00:00 12",
+        "Tugas 2: 04-09-2025",
+    ] {
+        assert_eq!(parse_datetime(text, WIB), None, "{}", text);
+    }
+}
@@ -0,0 +1,144 @@
+// tests/diff_test.rs
+
+//! Tests of the snapshot diff engine, using snapshots of the mock SPOT.
+
+mod common;
+
+use chrono::Duration;
+use common::{COURSE_ID, MockSpot, NIM, PASSWORD, TASK_ID, TOPIC_ID};
+use spot_scraper::{ChangeEvent, ScraperError, Snapshot, diff, diff_json};
+
+async fn snapshot(mock: &MockSpot) -> Snapshot {
+    let client = mock.client();
+    client.login(NIM, PASSWORD).await.unwrap();
+    client.snapshot().await.unwrap()
+}
+
+#[tokio::test]
+async fn identical_snapshots_have_no_changes() {
+    let mock = MockSpot::start().await;
+    let snapshot = snapshot(&mock).await;
+    assert_eq!(snapshot.courses.len(), 2);
+    assert_eq!(snapshot.courses[0].topics.len(), 2);
+    assert!(diff(&snapshot, &snapshot).is_empty());
+}
+
+#[tokio::test]
+async fn grading_is_reported_once_then_as_updates() {
+    let mock = MockSpot::start().await;
+    let ungraded = snapshot(&mock).await;
+    mock.set_answered(true);
+    mock.set_graded(true);
+    let graded = snapshot(&mock).await;
+
+    let events = diff(&ungraded, &graded);
+    assert_eq!(events.len(), 2);
+    match &events[0] {
+        ChangeEvent::AnswerGraded {
+            course_id,
+            topic_id,
+            task,
+            ..
+        } => {
+            assert_eq!(*course_id, COURSE_ID);
+            assert_eq!(*topic_id, TOPIC_ID);
+            assert_eq!(task.id, Some(TASK_ID));
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+
+    let mut regraded = graded.clone();
+    let answer = regraded.courses[0].topics[0].tasks[0]
+        .answer
+        .as_mut()
+        .unwrap();
+    let old_score = answer.score;
+    answer.score += 5.0;
+    answer.lecturer_notes = "Revisi diterima.".to_string();
+
+    let events = diff(&graded, &regraded);
+    assert!(matches!(
+        &events[..],
+        [
+            ChangeEvent::ScoreChanged { old, new, .. },
+            ChangeEvent::LecturerNotesChanged { .. },
+        ] if *old == old_score && *new == old_score + 5.0
+    ));
+}
+
+#[tokio::test]
+async fn topics_tasks_and_due_dates_are_tracked() {
+    let mock = MockSpot::start().await;
+    let new = snapshot(&mock).await;
+
+    // Pretend the old snapshot had the second topic still locked, lacked the
+    // third topic and the first topic's task, and had the second topic's task
+    // due a day earlier.
+    let mut old = new.clone();
+    let course = &mut old.courses[0];
+    course.detail.topics[1].is_accessible = false;
+    course.detail.topics[1].id = None;
+    course.detail.topics.pop();
+    course.topics[0].tasks.clear();
+    let due = course.topics[1].tasks[0].due_date.unwrap();
+    course.topics[1].tasks[0].due_date = Some(due - Duration::days(1));
+
+    let events = diff(&old, &new);
+    let topic_ids: Vec<Option<u64>> = new.courses[0].detail.topics.iter().map(|t| t.id).collect();
+    assert_eq!(
+        events,
+        vec![
+            ChangeEvent::TopicAccessible {
+                course_id: COURSE_ID,
+                topic_id: topic_ids[1].unwrap(),
+            },
+            ChangeEvent::TopicAdded {
+                course_id: COURSE_ID,
+                topic_id: topic_ids[2],
+                is_accessible: false,
+            },
+            ChangeEvent::TaskAdded {
+                course_id: COURSE_ID,
+                topic_id: TOPIC_ID,
+                task: (&new.courses[0].topics[0].tasks[0]).into(),
+                due_date: new.courses[0].topics[0].tasks[0].due_date,
+            },
+            ChangeEvent::DueDateChanged {
+                course_id: COURSE_ID,
+                topic_id: topic_ids[1].unwrap(),
+                task: (&new.courses[0].topics[1].tasks[0]).into(),
+                old: Some(due - Duration::days(1)),
+                new: Some(due),
+            },
+        ]
+    );
+
+    let mut without_course = new.clone();
+    without_course.courses.remove(0);
+    assert!(matches!(
+        &diff(&without_course, &new)[..],
+        [ChangeEvent::CourseAdded { course_id, .. }] if *course_id == COURSE_ID
+    ));
+}
+
+#[tokio::test]
+async fn json_snapshots_can_be_compared() {
+    let mock = MockSpot::start().await;
+    let old = serde_json::to_string(&snapshot(&mock).await).unwrap();
+    mock.set_answered(true);
+    mock.set_graded(true);
+    let new = serde_json::to_string(&snapshot(&mock).await).unwrap();
+
+    let events = diff_json(&old, &new).unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(
+        serde_json::to_string(&events[0])
+            .unwrap()
+            .contains("\"type\":\"answer_graded\"")
+    );
+
+    assert!(matches!(
+        diff_json("{}", &new),
+        Err(ScraperError::InvalidSnapshot(_))
+    ));
+}
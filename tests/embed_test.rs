@@ -0,0 +1,140 @@
+// tests/embed_test.rs
+
+use spot_scraper::{EmbedKind, classify_spot_url, classify_url};
+
+fn youtube(id: &str) -> Option<EmbedKind> {
+    Some(EmbedKind::YouTube {
+        video_id: id.to_string(),
+    })
+}
+
+#[test]
+fn youtube_variants_share_one_id() {
+    for url in [
+        "https://www.youtube.com/embed/dQw4w9WgXcQ?rel=0",
+        "https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ",
+        "https://youtu.be/dQw4w9WgXcQ?t=42",
+        "https://m.youtube.com/watch?v=dQw4w9WgXcQ&list=PL1",
+        "https://www.youtube.com/shorts/dQw4w9WgXcQ",
+    ] {
+        assert_eq!(classify_url(url), youtube("dQw4w9WgXcQ"), "{}", url);
+    }
+    assert_eq!(classify_url("https://www.youtube.com/@upi"), None);
+}
+
+#[test]
+fn google_workspace_links() {
+    assert_eq!(
+        classify_url("https://drive.google.com/file/d/1AbC_dEf/view?usp=sharing"),
+        Some(EmbedKind::GoogleDriveFile {
+            file_id: "1AbC_dEf".to_string()
+        })
+    );
+    assert_eq!(
+        classify_url("https://drive.google.com/open?id=1AbC_dEf"),
+        Some(EmbedKind::GoogleDriveFile {
+            file_id: "1AbC_dEf".to_string()
+        })
+    );
+    assert_eq!(
+        classify_url("https://drive.google.com/drive/folders/0BxYz"),
+        Some(EmbedKind::GoogleDriveFolder {
+            folder_id: "0BxYz".to_string()
+        })
+    );
+    assert_eq!(
+        classify_url("https://docs.google.com/document/d/1Doc/edit"),
+        Some(EmbedKind::GoogleDocs {
+            document_id: "1Doc".to_string()
+        })
+    );
+    assert_eq!(
+        classify_url("https://docs.google.com/a/upi.edu/spreadsheets/d/1Sheet/edit#gid=0"),
+        Some(EmbedKind::GoogleSheets {
+            spreadsheet_id: "1Sheet".to_string()
+        })
+    );
+    assert_eq!(
+        classify_url("https://docs.google.com/presentation/d/e/2PACX-1vS/embed?start=false"),
+        Some(EmbedKind::GoogleSlides {
+            presentation_id: "2PACX-1vS".to_string()
+        })
+    );
+    assert_eq!(
+        classify_url("https://docs.google.com/forms/d/e/1FAIpQL/viewform"),
+        Some(EmbedKind::GoogleForms {
+            form_id: "1FAIpQL".to_string()
+        })
+    );
+    assert_eq!(
+        classify_url("https://forms.gle/abc123"),
+        Some(EmbedKind::GoogleForms {
+            form_id: "abc123".to_string()
+        })
+    );
+}
+
+#[test]
+fn meeting_links() {
+    assert_eq!(
+        classify_url("https://meet.google.com/abc-defg-hij"),
+        Some(EmbedKind::GoogleMeet {
+            meeting_code: "abc-defg-hij".to_string()
+        })
+    );
+    assert_eq!(
+        classify_url("https://upi-edu.zoom.us/j/81234567890?pwd=s3cr3t"),
+        Some(EmbedKind::Zoom {
+            meeting_id: Some("81234567890".to_string()),
+            passcode: Some("s3cr3t".to_string()),
+        })
+    );
+}
+
+#[test]
+fn office_and_direct_file_links() {
+    assert_eq!(
+        classify_url("https://upiedu-my.sharepoint.com/:p:/g/personal/x/EaBc"),
+        Some(EmbedKind::MicrosoftOffice)
+    );
+    assert_eq!(
+        classify_url("https://1drv.ms/w/s!AbC"),
+        Some(EmbedKind::MicrosoftOffice)
+    );
+    assert_eq!(classify_url("https://notoffice.com/x"), None);
+    assert_eq!(
+        classify_url("https://spot.upi.edu/uploads/materi/Modul%201.PDF"),
+        Some(EmbedKind::DirectFile {
+            extension: Some("pdf".to_string())
+        })
+    );
+    assert_eq!(
+        classify_url("https://spot.upi.edu/mhs/download/2510009533/1370508/163205"),
+        Some(EmbedKind::DirectFile { extension: None })
+    );
+    assert_eq!(classify_url("https://spot.upi.edu/mhs"), None);
+}
+
+#[test]
+fn download_paths_only_count_as_files_on_spot() {
+    assert_eq!(classify_url("https://example.com/download/report"), None);
+    assert_eq!(
+        classify_url("https://example.com/uploads/materi/modul"),
+        None
+    );
+    assert_eq!(
+        classify_spot_url("http://127.0.0.1/mhs/download/1/2/3", "127.0.0.1"),
+        Some(EmbedKind::DirectFile { extension: None })
+    );
+    assert_eq!(
+        classify_spot_url("https://spot.upi.edu/mhs/download/1/2/3", "127.0.0.1"),
+        None
+    );
+    // A file extension is enough on any host.
+    assert_eq!(
+        classify_url("https://example.com/download/modul.pdf"),
+        Some(EmbedKind::DirectFile {
+            extension: Some("pdf".to_string())
+        })
+    );
+}
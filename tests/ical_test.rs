@@ -0,0 +1,92 @@
+// tests/ical_test.rs
+
+//! Tests of the iCalendar export, using a snapshot of the mock SPOT.
+
+mod common;
+
+use chrono::Duration;
+use common::{COURSE_ID, MockSpot, NIM, PASSWORD, TASK_ID, TOPIC_ID};
+use spot_scraper::{CalendarExporter, Snapshot};
+
+async fn snapshot() -> Snapshot {
+    let mock = MockSpot::start().await;
+    let client = mock.client();
+    client.login(NIM, PASSWORD).await.unwrap();
+    client.snapshot().await.unwrap()
+}
+
+/// Undoes line folding so properties can be matched whole.
+fn unfold(ics: &str) -> String {
+    ics.replace("\r\n ", "")
+}
+
+#[tokio::test]
+async fn tasks_become_events_with_stable_uids() {
+    let snapshot = snapshot().await;
+    let ics = CalendarExporter::new()
+        .base_url("https://spot.upi.edu/")
+        .reminder(Duration::hours(24))
+        .reminder(Duration::minutes(90))
+        .export(&snapshot);
+
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert!(ics.lines().all(|line| line.len() <= 75));
+    assert!(!ics.replace("\r\n", "").contains('\n'));
+
+    let ics = unfold(&ics);
+    // Both accessible topics serve a task; each gets its own event.
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+    assert!(ics.contains(&format!(
+        "UID:task-{}-{}-{}@spot.upi.edu\r\n",
+        COURSE_ID, TOPIC_ID, TASK_ID
+    )));
+    // 01-09-2025 07:00 to 31-12-2099 23:59 WIB, in UTC.
+    assert!(ics.contains("DTSTART:20250901T000000Z\r\n"));
+    assert!(ics.contains("DTEND:20991231T165900Z\r\n"));
+    assert!(ics.contains("SUMMARY:PROYEK KONSULTANSI: Proposal Mitra\r\n"));
+    assert!(ics.contains(&format!(
+        "URL:https://spot.upi.edu/mhs/topik/{}/{}\r\n",
+        COURSE_ID, TOPIC_ID
+    )));
+    assert!(ics.contains("DESCRIPTION:PROYEK KONSULTANSI\\n\\nUnggah proposal kerjasama"));
+    assert!(ics.contains("TRIGGER;RELATED=END:-PT1440M\r\n"));
+    assert!(ics.contains("TRIGGER;RELATED=END:-PT90M\r\n"));
+    assert_eq!(ics.matches("BEGIN:VALARM").count(), 4);
+
+    // A second export of the same tree produces the same events.
+    let again = unfold(&CalendarExporter::new().export(&snapshot));
+    let uids = |ics: &str| {
+        ics.lines()
+            .filter(|line| line.starts_with("UID:"))
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(uids(&ics), uids(&again));
+}
+
+#[tokio::test]
+async fn topic_access_times_are_optional_events() {
+    let snapshot = snapshot().await;
+    let without = CalendarExporter::new().export(&snapshot);
+    assert!(!without.contains("UID:topic-"));
+
+    let ics = unfold(
+        &CalendarExporter::new()
+            .topic_access_times(true)
+            .export(&snapshot),
+    );
+    assert_eq!(ics.matches("UID:topic-").count(), 3);
+    // Open topics are keyed by id, so reordering them keeps their events.
+    assert!(ics.contains(&format!(
+        "UID:topic-{}-{}@spot.upi.edu\r\n",
+        COURSE_ID, TOPIC_ID
+    )));
+    // The third topic is still locked but already has an access time.
+    assert!(ics.contains(&format!(
+        "UID:topic-{}-locked-3@spot.upi.edu\r\n",
+        COURSE_ID
+    )));
+    assert!(ics.contains("DTSTART:20251218T000000Z\r\n"));
+    assert!(ics.contains("SUMMARY:PROYEK KONSULTANSI: topic 3 opens\r\n"));
+}
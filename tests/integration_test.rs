@@ -12,9 +12,13 @@ use std::io::Write;
 ///
 /// All output is written to `test_output.log` for easy inspection.
 ///
+/// It talks to the real SPOT server, so it is ignored by default; the offline
+/// suite in `client_test.rs` covers the same flow against a mock server.
+///
 /// To run this test:
-/// SPOT_NIM="your_nim" SPOT_PASSWORD="your_password" cargo test -- --nocapture
+/// SPOT_NIM="your_nim" SPOT_PASSWORD="your_password" cargo test -- --ignored --nocapture
 #[tokio::test]
+#[ignore]
 async fn test_full_login_and_scrape_flow() -> Result<()> {
     dotenv().ok();
     // --- SETUP: Create a log file to store all output ---
@@ -0,0 +1,151 @@
+// tests/login_test.rs
+
+//! Tests of how failed CAS logins are diagnosed and dumped.
+
+mod common;
+
+use common::{MockSpot, NIM, PASSWORD, fixture, html_with_status};
+use spot_scraper::{LoginFailure, ScraperError};
+use std::sync::{Arc, Mutex};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Makes CAS answer the login form with `response` instead of the usual flow.
+async fn cas_answers(mock: &MockSpot, response: ResponseTemplate) {
+    Mock::given(method("POST"))
+        .and(path("/cas/login"))
+        .respond_with(response)
+        .with_priority(1)
+        .mount(&mock.cas)
+        .await;
+}
+
+fn login_page_with(error_html: &str) -> String {
+    fixture("cas_login.html").replace("<!-- ERROR -->", error_html)
+}
+
+async fn failure(mock: &MockSpot) -> (LoginFailure, Option<String>, String) {
+    match mock.client().login(NIM, PASSWORD).await {
+        Err(ScraperError::AuthenticationFailed {
+            reason,
+            message,
+            url,
+        }) => (reason, message, url),
+        other => panic!("expected a failed login, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn locked_account_is_reported() {
+    let mock = MockSpot::start().await;
+    let page = login_page_with(
+        "<div id=\"msg\" class=\"errors\">Akun Anda terkunci. Hubungi admin.</div>",
+    );
+    cas_answers(&mock, html_with_status(401, page)).await;
+
+    let (reason, message, _) = failure(&mock).await;
+    assert_eq!(reason, LoginFailure::AccountLocked);
+    assert_eq!(
+        message.as_deref(),
+        Some("Akun Anda terkunci. Hubungi admin.")
+    );
+}
+
+#[tokio::test]
+async fn captcha_is_reported() {
+    let mock = MockSpot::start().await;
+    let page = login_page_with("<div class=\"g-recaptcha\" data-sitekey=\"x\"></div>");
+    cas_answers(&mock, html_with_status(200, page)).await;
+
+    let (reason, message, _) = failure(&mock).await;
+    assert_eq!(reason, LoginFailure::CaptchaRequired);
+    assert_eq!(message, None);
+}
+
+#[tokio::test]
+async fn maintenance_page_without_a_form_is_reported() {
+    let mock = MockSpot::start().await;
+    Mock::given(method("GET"))
+        .and(path("/cas/login"))
+        .respond_with(html_with_status(
+            503,
+            "<html><head><title>Maintenance</title></head>\
+             <body><h1>SSO sedang dalam pemeliharaan</h1></body></html>"
+                .to_string(),
+        ))
+        .with_priority(1)
+        .mount(&mock.cas)
+        .await;
+
+    let (reason, _, url) = failure(&mock).await;
+    assert_eq!(reason, LoginFailure::Maintenance);
+    assert!(url.contains("/cas/login"), "{}", url);
+    assert_eq!(mock.logins(), 0);
+}
+
+#[tokio::test]
+async fn redirect_to_a_foreign_site_is_reported() {
+    let mock = MockSpot::start().await;
+    let elsewhere = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(html_with_status(200, "<html><body>Hi</body></html>".into()))
+        .mount(&elsewhere)
+        .await;
+    cas_answers(
+        &mock,
+        ResponseTemplate::new(302).insert_header("location", format!("{}/portal", elsewhere.uri())),
+    )
+    .await;
+
+    let (reason, message, url) = failure(&mock).await;
+    assert_eq!(reason, LoginFailure::UnexpectedRedirect);
+    assert_eq!(message, None);
+    assert_eq!(url, format!("{}/portal", elsewhere.uri()));
+}
+
+#[tokio::test]
+async fn failed_logins_are_dumped_to_the_debug_directory() {
+    let mock = MockSpot::start().await;
+    let dir = std::env::temp_dir().join(format!("spot-dump-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let client = mock.builder().debug_dump_dir(&dir).build().unwrap();
+
+    assert!(client.login(NIM, "salah").await.is_err());
+
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 1);
+    assert!(files[0].to_string_lossy().ends_with("-login.html"));
+    let dumped = std::fs::read_to_string(&files[0]).unwrap();
+    assert!(dumped.contains("Invalid credentials."));
+    assert!(dumped.contains("/cas/login"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn pages_are_only_dumped_when_asked_to() {
+    let mock = MockSpot::start().await;
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&seen);
+    let client = mock
+        .builder()
+        .debug_dump(move |page: &spot_scraper::FailedPage<'_>| {
+            sink.lock()
+                .unwrap()
+                .push((page.kind.to_string(), page.error.to_string()));
+        })
+        .build()
+        .unwrap();
+
+    client.login(NIM, PASSWORD).await.unwrap();
+    client.get_courses().await.unwrap();
+    assert!(seen.lock().unwrap().is_empty());
+
+    assert!(client.login(NIM, "salah").await.is_err());
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 1);
+    assert_eq!(seen[0].0, "login");
+    assert!(seen[0].1.contains("wrong NIM or password"), "{}", seen[0].1);
+}
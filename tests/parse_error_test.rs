@@ -0,0 +1,177 @@
+// tests/parse_error_test.rs
+
+//! Tests of the context parsing errors carry when SPOT changes its markup.
+
+mod common;
+
+use common::{COURSE_ID, MockSpot, NIM, PASSWORD, fixture, html_with_status};
+use spot_scraper::{
+    Language, PageElement, PageKind, ParseProblem, RetryPolicy, Rps, ScraperError, SpotClient,
+    TopicInfo,
+};
+use wiremock::Mock;
+use wiremock::matchers::{method, path};
+
+/// Serves `body` for `page_path` instead of the recorded page.
+async fn serve(mock: &MockSpot, page_path: &str, body: &str) {
+    Mock::given(method("GET"))
+        .and(path(page_path))
+        .respond_with(html_with_status(200, body.to_string()))
+        .with_priority(1)
+        .mount(&mock.spot)
+        .await;
+}
+
+async fn logged_in(mock: &MockSpot) -> SpotClient {
+    let client = mock.builder().retry(RetryPolicy::none()).build().unwrap();
+    client.login(NIM, PASSWORD).await.unwrap();
+    client
+}
+
+#[tokio::test]
+async fn missing_element_names_page_selector_and_url() {
+    let mock = MockSpot::start().await;
+    let redesigned = fixture("mhs.html").replace("profile-text", "profile-name");
+    serve(&mock, "/mhs", &redesigned).await;
+    let client = logged_in(&mock).await;
+
+    let error = client.get_user_profile().await.unwrap_err();
+    match &error {
+        ScraperError::ElementNotFound {
+            page,
+            element,
+            selector,
+            url,
+            excerpt,
+        } => {
+            assert_eq!(*page, PageKind::Dashboard);
+            assert_eq!(*element, PageElement::ProfileText);
+            assert_eq!(*selector, ".user-profile .profile-text");
+            assert_eq!(
+                url.as_deref(),
+                Some(format!("{}/mhs", mock.spot.uri()).as_str())
+            );
+            assert!(excerpt.as_deref().unwrap().contains("profile-name"));
+        }
+        other => panic!("expected a missing element, got {:?}", other),
+    }
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "Could not find the profile text on the dashboard page \
+             (selector `.user-profile .profile-text`) at {}/mhs",
+            mock.spot.uri()
+        )
+    );
+    assert_eq!(
+        error.localized(Language::Indonesian),
+        format!(
+            "Tidak menemukan teks profil di halaman dasbor \
+             (selector `.user-profile .profile-text`) di {}/mhs",
+            mock.spot.uri()
+        )
+    );
+}
+
+#[tokio::test]
+async fn a_broken_course_row_is_reported_with_its_html() {
+    let mock = MockSpot::start().await;
+    let page = fixture("mhs.html")
+        .replace("<td>4</td>", "")
+        .replace("<td>2</td>", "");
+    serve(&mock, "/mhs", &page).await;
+    let client = logged_in(&mock).await;
+
+    match client.get_courses().await {
+        Err(ScraperError::ParsingError {
+            page,
+            problem,
+            selector,
+            url,
+            excerpt,
+        }) => {
+            assert_eq!(page, PageKind::Dashboard);
+            assert_eq!(problem, ParseProblem::TooFewColumns);
+            assert_eq!(selector, Some("td"));
+            assert!(url.unwrap().ends_with("/mhs"));
+            let excerpt = excerpt.unwrap();
+            assert!(excerpt.starts_with("<tr>"), "{}", excerpt);
+            assert!(excerpt.contains("PT502"), "{}", excerpt);
+        }
+        other => panic!("expected a parsing error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn empty_rps_page_is_reported() {
+    let mock = MockSpot::start().await;
+    let rps_path = format!("/mhs/rps/{}", COURSE_ID);
+    serve(
+        &mock,
+        &rps_path,
+        "<html><body><p>Belum ada RPS</p></body></html>",
+    )
+    .await;
+    let client = logged_in(&mock).await;
+
+    let rps = Rps {
+        id: Some(COURSE_ID),
+        href: Some(rps_path.clone()),
+    };
+    match client.get_rps(&rps).await {
+        Err(ScraperError::ElementNotFound {
+            page,
+            element,
+            url,
+            excerpt,
+            ..
+        }) => {
+            assert_eq!(page, PageKind::Rps);
+            assert_eq!(element, PageElement::RpsContent);
+            assert!(url.unwrap().ends_with(&rps_path));
+            assert_eq!(
+                excerpt.as_deref(),
+                Some("<body><p>Belum ada RPS</p></body>")
+            );
+        }
+        other => panic!("expected a missing element, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn incomplete_topic_links_are_rejected_before_fetching() {
+    let mock = MockSpot::start().await;
+    let client = mock.client();
+    let topic = TopicInfo {
+        id: None,
+        course_id: Some(COURSE_ID),
+        access_time: None,
+        is_accessible: true,
+        href: Some(format!("/mhs/topik/{}/1", COURSE_ID)),
+    };
+
+    let error = client.get_topic_detail(&topic).await.unwrap_err();
+    assert!(matches!(
+        error,
+        ScraperError::ParsingError {
+            page: PageKind::TopicDetail,
+            problem: ParseProblem::MissingTopicId,
+            url: None,
+            ..
+        }
+    ));
+    assert_eq!(
+        error.to_string(),
+        "Could not parse the topic page: no topic id"
+    );
+    assert_eq!(
+        error.localized(Language::Indonesian),
+        "Gagal membaca halaman topik: tidak ada id topik"
+    );
+}
+
+#[test]
+fn other_errors_fall_back_to_english() {
+    let error = ScraperError::SessionExpired;
+    assert_eq!(error.localized(Language::Indonesian), error.to_string());
+}
@@ -0,0 +1,115 @@
+// tests/rate_limit_test.rs
+
+//! Tests of the client's per-host rate limiting and concurrency cap.
+
+mod common;
+
+use common::{MockSpot, NIM, PASSWORD};
+use spot_scraper::{RateLimit, SpotClient};
+use std::time::{Duration, Instant};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn requests_to_one_host_are_spaced_out() {
+    let mock = MockSpot::start().await;
+    let client = mock
+        .builder()
+        .rate_limit(RateLimit::per_second(20.0))
+        .build()
+        .unwrap();
+    client.login(NIM, PASSWORD).await.unwrap();
+
+    let started = Instant::now();
+    for _ in 0..6 {
+        client.get_user_profile().await.unwrap();
+    }
+    // The first request may use the token saved up during login; the other
+    // five wait 50ms each.
+    assert!(started.elapsed() >= Duration::from_millis(240));
+}
+
+#[tokio::test]
+async fn hosts_are_throttled_independently() {
+    let mock = MockSpot::start().await;
+    // Reach CAS through another host name than SPOT, as in production.
+    let cas = mock.cas.uri().replace("127.0.0.1", "localhost");
+    let client = mock
+        .builder()
+        .sso_login_url(format!("{}/cas/login", cas))
+        .rate_limit(RateLimit::per_second(2.0))
+        .host_rate_limit("localhost", RateLimit::unlimited())
+        .build()
+        .unwrap();
+
+    client.login(NIM, PASSWORD).await.unwrap();
+    client.get_user_profile().await.unwrap();
+
+    // Logging in again only talks to CAS, so the empty SPOT bucket does not
+    // hold it up.
+    let started = Instant::now();
+    client.login(NIM, PASSWORD).await.unwrap();
+    assert!(started.elapsed() < Duration::from_millis(400));
+
+    // The next SPOT page still waits for its token.
+    let started = Instant::now();
+    client.get_user_profile().await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(350));
+}
+
+#[tokio::test]
+async fn concurrent_requests_are_capped() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(100)))
+        .mount(&server)
+        .await;
+    let client = SpotClient::builder()
+        .base_url(server.uri())
+        .rate_limit(RateLimit::unlimited())
+        .max_concurrency(1)
+        .build()
+        .unwrap();
+
+    let started = Instant::now();
+    let (mut a, mut b, mut c) = (Vec::new(), Vec::new(), Vec::new());
+    let (first, second, third) = tokio::join!(
+        client.download("/a", &mut a),
+        client.download("/b", &mut b),
+        client.download("/c", &mut c),
+    );
+    first.unwrap();
+    second.unwrap();
+    third.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn a_request_slot_is_held_until_the_body_is_read() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![b'x'; 64 * 1024]))
+        .mount(&server)
+        .await;
+    let client = SpotClient::builder()
+        .base_url(server.uri())
+        .rate_limit(RateLimit::unlimited())
+        .max_concurrency(1)
+        .build()
+        .unwrap();
+
+    // The reader never reads, so the first download stalls halfway through.
+    let (mut writer, reader) = tokio::io::duplex(16);
+    let (stalled, next) = tokio::join!(client.download("/big", &mut writer), async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let mut sink = Vec::new();
+        let waiting =
+            tokio::time::timeout(Duration::from_millis(300), client.download("/b", &mut sink))
+                .await;
+        assert!(waiting.is_err(), "the second request did not wait");
+        drop(reader);
+        client.download("/b", &mut sink).await
+    });
+    assert!(stalled.is_err());
+    assert_eq!(next.unwrap().size, 64 * 1024);
+}
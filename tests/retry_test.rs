@@ -0,0 +1,107 @@
+// tests/retry_test.rs
+
+//! Tests of status-code checking and retries of transient failures.
+
+mod common;
+
+use common::fixture;
+use reqwest::StatusCode;
+use spot_scraper::{RateLimit, RetryPolicy, ScraperError, SpotClient};
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client(server: &MockServer, retry: RetryPolicy) -> SpotClient {
+    SpotClient::builder()
+        .base_url(server.uri())
+        .rate_limit(RateLimit::unlimited())
+        .retry(retry.initial_delay(Duration::from_millis(10)))
+        .timeout(Duration::from_millis(200))
+        .build()
+        .unwrap()
+}
+
+async fn requests(server: &MockServer) -> usize {
+    server.received_requests().await.unwrap().len()
+}
+
+#[tokio::test]
+async fn server_errors_are_retried() {
+    let server = MockServer::start().await;
+    Mock::given(path("/mhs"))
+        .respond_with(ResponseTemplate::new(502))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(path("/mhs"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(fixture("mhs.html")))
+        .mount(&server)
+        .await;
+
+    let user = client(&server, RetryPolicy::new(3))
+        .get_user_profile()
+        .await
+        .unwrap();
+    assert_eq!(user.nim, common::NIM);
+    assert_eq!(requests(&server).await, 3);
+}
+
+#[tokio::test]
+async fn error_pages_are_not_parsed_or_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("<h1>Not Found</h1>"))
+        .mount(&server)
+        .await;
+
+    let err = client(&server, RetryPolicy::new(3))
+        .get_user_profile()
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, ScraperError::HttpStatus { status, url, .. }
+            if *status == StatusCode::NOT_FOUND && url.ends_with("/mhs")),
+        "{:?}",
+        err
+    );
+    assert!(!err.is_retryable());
+    assert_eq!(requests(&server).await, 1);
+}
+
+#[tokio::test]
+async fn retries_stop_after_max_attempts() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503).insert_header("Retry-After", "0"))
+        .mount(&server)
+        .await;
+
+    let err = client(&server, RetryPolicy::new(2))
+        .get_courses()
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    assert!(err.is_retryable());
+    assert_eq!(requests(&server).await, 2);
+}
+
+#[tokio::test]
+async fn timeouts_are_retryable() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(2)))
+        .mount(&server)
+        .await;
+
+    let err = client(&server, RetryPolicy::none())
+        .get_user_profile()
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, ScraperError::RequestError(e) if e.is_timeout()),
+        "{:?}",
+        err
+    );
+    assert!(err.is_retryable());
+    assert_eq!(requests(&server).await, 1);
+}
@@ -0,0 +1,177 @@
+// tests/storage_test.rs
+
+//! Tests of the SQLite archive and incremental sync against the mock SPOT.
+
+#![cfg(feature = "storage")]
+
+mod common;
+
+use common::{
+    ANSWER_ID, COURSE_ID, MockSpot, NIM, PASSWORD, TASK_ID, TOPIC_ID, fixture, html_with_status,
+};
+use spot_scraper::{RecordKind, Storage, SyncReport, Task, TaskStatus};
+use std::time::Duration;
+use wiremock::Mock;
+use wiremock::matchers::{method, path, path_regex};
+
+#[tokio::test]
+async fn sync_archives_records_and_their_changes() {
+    let mock = MockSpot::start().await;
+    let client = mock.client();
+    client.login(NIM, PASSWORD).await.unwrap();
+    let storage = Storage::open_in_memory()
+        .unwrap()
+        .max_topic_age(Duration::ZERO);
+
+    // First run: everything is new, both accessible topics are fetched.
+    let report = storage.sync(&client).await.unwrap();
+    assert_eq!(report.courses, 2);
+    assert_eq!(report.topics_fetched, 2);
+    assert_eq!(report.topics_skipped, 0);
+    assert!(report.changed_records > 0);
+
+    assert_eq!(storage.user(NIM).unwrap().unwrap().record.nim, NIM);
+    assert_eq!(storage.courses().unwrap().len(), 2);
+    let topic = storage.topic_detail(TOPIC_ID).unwrap().unwrap();
+    assert_eq!(topic.record.tasks.len(), 1);
+    assert_eq!(topic.record.tasks[0].id, Some(TASK_ID));
+    assert!(topic.record.tasks[0].token.is_empty());
+    assert_eq!(storage.tasks().unwrap().len(), 2);
+
+    // The topics are stale at once, so the new answer and grade are archived.
+    mock.set_answered(true);
+    mock.set_graded(true);
+    let report = storage.sync(&client).await.unwrap();
+    assert_eq!(report.topics_fetched, 2);
+    let answer = storage.answer(ANSWER_ID).unwrap().unwrap();
+    assert!(answer.record.is_graded);
+    assert!(answer.first_seen <= answer.last_seen);
+
+    // Refetching unchanged topics records nothing new.
+    let report = storage.sync(&client).await.unwrap();
+    assert_eq!(
+        report,
+        SyncReport {
+            courses: 2,
+            topics_fetched: 2,
+            topics_skipped: 0,
+            changed_records: 0,
+        }
+    );
+
+    let history = storage
+        .history::<Task>(RecordKind::Task, &TASK_ID.to_string())
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    assert!(history[0].record.answer.is_none());
+    assert!(history[1].record.answer.as_ref().unwrap().is_graded);
+    // The status follows the clock, so the archive leaves it out.
+    let raw = storage
+        .history::<serde_json::Value>(RecordKind::Task, &TASK_ID.to_string())
+        .unwrap();
+    assert!(raw.iter().all(|seen| seen.record.get("status").is_none()));
+    assert_eq!(history[1].record.status(), TaskStatus::Graded);
+
+    let detail = storage.course_detail(COURSE_ID).unwrap().unwrap();
+    assert_eq!(detail.record.topics.len(), 3);
+}
+
+#[tokio::test]
+async fn unchanged_topics_are_not_refetched_until_they_are_stale() {
+    let mock = MockSpot::start().await;
+    let client = mock.client();
+    client.login(NIM, PASSWORD).await.unwrap();
+    let storage = Storage::open_in_memory().unwrap();
+    assert_eq!(storage.sync(&client).await.unwrap().topics_fetched, 2);
+
+    // The task is still open, but the course page shows no change.
+    Mock::given(method("GET"))
+        .and(path_regex(r"^/mhs/topik/"))
+        .respond_with(html_with_status(500, String::new()))
+        .with_priority(1)
+        .expect(0)
+        .mount(&mock.spot)
+        .await;
+    let report = storage.sync(&client).await.unwrap();
+    assert_eq!(report.topics_fetched, 0);
+    assert_eq!(report.topics_skipped, 2);
+    assert_eq!(report.changed_records, 0);
+}
+
+#[tokio::test]
+async fn archive_persists_across_reopen() {
+    let mock = MockSpot::start().await;
+    let client = mock.client();
+    client.login(NIM, PASSWORD).await.unwrap();
+
+    let path = std::env::temp_dir().join(format!("spot-archive-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    {
+        let storage = Storage::open(&path).unwrap();
+        storage.sync(&client).await.unwrap();
+    }
+
+    let storage = Storage::open(&path).unwrap();
+    let course = &storage.courses().unwrap()[0];
+    assert_eq!(course.record.id, COURSE_ID);
+    assert!(course.first_seen <= course.last_seen);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn tasks_added_to_a_graded_topic_are_archived_once_it_is_stale() {
+    let mock = MockSpot::start().await;
+    let client = mock.client();
+    client.login(NIM, PASSWORD).await.unwrap();
+    mock.set_answered(true);
+    mock.set_graded(true);
+    let storage = Storage::open_in_memory()
+        .unwrap()
+        .max_topic_age(Duration::ZERO);
+    storage.sync(&client).await.unwrap();
+    assert_eq!(
+        storage
+            .topic_detail(TOPIC_ID)
+            .unwrap()
+            .unwrap()
+            .record
+            .tasks
+            .len(),
+        1
+    );
+
+    // The lecturer adds a second task to the fully graded topic.
+    let graded = fixture("topic.html").replace(
+        "<!-- ANSWER -->",
+        &fixture("topic_answer.html").replace("<!-- GRADE -->", &fixture("topic_grade.html")),
+    );
+    let new_task = r#"<table class="table table-striped"><tbody>
+        <tr><td>Judul</td><td>: Laporan Akhir</td></tr>
+        <tr><td>Waktu Pengumpulan</td><td>: <b>01-09-2025 07:00</b> s/d <b>31-12-2099 23:59</b></td></tr>
+      </tbody></table>
+      <div class="modal fade" id="modalTugas88099" role="dialog">
+        <input type="hidden" name="_token" value="mock-csrf-token">
+        <input type="hidden" name="id_tg" value="88099">
+      </div>
+    </div>
+</body>"#;
+    let page = graded.replacen("  </div>\n</body>", new_task, 1);
+    assert!(page.contains("88099"));
+    Mock::given(method("GET"))
+        .and(path(format!("/mhs/topik/{}/{}", COURSE_ID, TOPIC_ID)))
+        .respond_with(html_with_status(200, page))
+        .with_priority(1)
+        .mount(&mock.spot)
+        .await;
+
+    let report = storage.sync(&client).await.unwrap();
+    assert_eq!(report.topics_fetched, 2);
+    let tasks = storage
+        .topic_detail(TOPIC_ID)
+        .unwrap()
+        .unwrap()
+        .record
+        .tasks;
+    assert_eq!(tasks.len(), 2);
+    assert!(tasks.iter().any(|task| task.id == Some(88099)));
+}
@@ -0,0 +1,157 @@
+// tests/task_status_test.rs
+
+use chrono::{DateTime, FixedOffset};
+use spot_scraper::{
+    Answer, Course, CourseTask, Task, TaskFilter, TaskStatus, TopicInfo, WIB, parse_datetime,
+};
+
+fn dt(s: &str) -> DateTime<FixedOffset> {
+    parse_datetime(s, WIB).unwrap()
+}
+
+fn answer(submitted: &str, is_graded: bool) -> Answer {
+    Answer {
+        id: Some(1),
+        content: String::new(),
+        file_href: None,
+        is_graded,
+        lecturer_notes: String::new(),
+        lecturer_notes_blocks: Vec::new(),
+        score: 0.0,
+        date_submitted: Some(dt(submitted)),
+        delete_href: None,
+    }
+}
+
+fn task(id: u64, start: &str, due: &str) -> Task {
+    Task {
+        id: Some(id),
+        course_id: 1,
+        topic_id: 1,
+        token: String::new(),
+        title: format!("Tugas {}", id),
+        description: String::new(),
+        description_blocks: Vec::new(),
+        file: None,
+        start_date: Some(dt(start)),
+        due_date: Some(dt(due)),
+        answer: None,
+        form: None,
+    }
+}
+
+#[test]
+fn unanswered_tasks_follow_the_submission_window() {
+    let start = Some(dt("01-09-2025 07:00"));
+    let due = Some(dt("08-09-2025 23:59"));
+
+    let status = |now| TaskStatus::derive(start, due, None, dt(now));
+    assert_eq!(status("31-08-2025 12:00"), TaskStatus::NotYetOpen);
+    assert_eq!(status("01-09-2025 07:00"), TaskStatus::Pending);
+    assert_eq!(status("08-09-2025 23:59"), TaskStatus::Pending);
+    assert_eq!(status("09-09-2025 00:00"), TaskStatus::Overdue);
+}
+
+#[test]
+fn unanswered_tasks_without_a_window_are_not_submitted() {
+    let now = dt("01-09-2025 07:00");
+    assert_eq!(
+        TaskStatus::derive(None, None, None, now),
+        TaskStatus::NotSubmitted
+    );
+    assert_eq!(
+        TaskStatus::derive(None, Some(dt("02-09-2025 07:00")), None, now),
+        TaskStatus::Pending
+    );
+}
+
+#[test]
+fn answers_are_on_time_late_or_graded() {
+    let start = Some(dt("01-09-2025 07:00"));
+    let due = Some(dt("08-09-2025 23:59"));
+    let now = dt("20-09-2025 10:00");
+
+    let on_time = answer("08-09-2025 23:00", false);
+    let late = answer("09-09-2025 08:00", false);
+    let graded_late = answer("09-09-2025 08:00", true);
+
+    assert_eq!(
+        TaskStatus::derive(start, due, Some(&on_time), now),
+        TaskStatus::Submitted
+    );
+    assert_eq!(
+        TaskStatus::derive(start, due, Some(&late), now),
+        TaskStatus::Late
+    );
+    assert_eq!(
+        TaskStatus::derive(start, due, Some(&graded_late), now),
+        TaskStatus::Graded
+    );
+}
+
+#[test]
+fn deadlines_compare_by_instant_not_wall_clock() {
+    let due = Some(dt("08-09-2025 23:59"));
+    // 17:00 UTC is already past midnight in Jakarta.
+    let now = parse_datetime("08-09-2025 17:00 UTC", WIB).unwrap();
+    assert_eq!(
+        TaskStatus::derive(None, due, None, now),
+        TaskStatus::Overdue
+    );
+}
+
+#[test]
+fn status_is_derived_on_read_and_serialized() {
+    let task = task(1, "01-09-2025 07:00", "08-09-2025 23:59");
+    assert_eq!(task.status_at(dt("05-09-2025 12:00")), TaskStatus::Pending);
+    assert_eq!(task.status_at(dt("09-09-2025 12:00")), TaskStatus::Overdue);
+
+    // The deadline is long gone, so the JSON reports the task as overdue.
+    let json = serde_json::to_value(&task).unwrap();
+    assert_eq!(json["status"], "Overdue");
+    let read_back: Task = serde_json::from_value(json).unwrap();
+    assert_eq!(read_back.status(), TaskStatus::Overdue);
+}
+
+#[test]
+fn pending_keeps_open_and_upcoming_tasks_and_overdue_ones_on_request() {
+    let course_task = |task: Task| CourseTask {
+        course: Course {
+            id: 1,
+            code: "PT502".to_string(),
+            name: String::new(),
+            credits: 2,
+            lecturer: String::new(),
+            academic_year: String::new(),
+            href: String::new(),
+        },
+        topic: TopicInfo {
+            id: Some(1),
+            course_id: Some(1),
+            access_time: None,
+            is_accessible: true,
+            href: None,
+        },
+        task,
+    };
+    let mut undated = task(4, "01-09-2025 07:00", "01-09-2025 07:00");
+    undated.start_date = None;
+    undated.due_date = None;
+    let tasks = vec![
+        course_task(task(1, "01-09-2025 07:00", "01-09-2099 07:00")),
+        course_task(task(2, "01-09-2098 07:00", "08-09-2098 07:00")),
+        course_task(task(3, "01-09-2025 07:00", "08-09-2025 23:59")),
+        course_task(undated),
+    ];
+    let ids = |filter: TaskFilter| -> Vec<_> {
+        filter
+            .apply(tasks.clone())
+            .iter()
+            .map(|t| t.task.id.unwrap())
+            .collect()
+    };
+
+    // Undated tasks sort last.
+    assert_eq!(ids(TaskFilter::pending()), [2, 1, 4]);
+    assert_eq!(ids(TaskFilter::pending().overdue()), [3, 2, 1, 4]);
+}
@@ -0,0 +1,307 @@
+// tests/watch_test.rs
+
+//! Tests of the watcher against the mock SPOT and a mock webhook receiver.
+
+mod common;
+
+use common::{MockSpot, NIM, PASSWORD, TASK_ID, fixture, html_with_status};
+use serde_json::Value;
+use spot_scraper::{SpotClient, Watcher, WebhookFormat};
+use std::path::PathBuf;
+use std::time::Duration;
+use wiremock::matchers::{method, path, path_regex};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn logged_in_client(mock: &MockSpot) -> SpotClient {
+    let client = mock.client();
+    client.login(NIM, PASSWORD).await.unwrap();
+    client
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[tokio::test]
+async fn changes_are_posted_to_webhooks() {
+    let mock = MockSpot::start().await;
+    let hooks = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&hooks)
+        .await;
+
+    let mut watcher = Watcher::builder(logged_in_client(&mock).await)
+        .webhook(&format!("{}/hook", hooks.uri()), WebhookFormat::Json)
+        .unwrap()
+        .webhook(&format!("{}/hook", hooks.uri()), WebhookFormat::Discord)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // The first poll only records the baseline.
+    let report = watcher.poll_once().await.unwrap();
+    assert!(report.events.is_empty());
+    assert!(hooks.received_requests().await.unwrap().is_empty());
+
+    mock.set_answered(true);
+    mock.set_graded(true);
+    let report = watcher.poll_once().await.unwrap();
+    assert_eq!(report.events.len(), 2);
+    assert_eq!(report.delivered, 2);
+    assert_eq!(report.failed, 0);
+
+    let requests = hooks.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    let json: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(json["events"][0]["type"], "answer_graded");
+    assert_eq!(json["events"][0]["task"]["id"], TASK_ID);
+    let discord: Value = serde_json::from_slice(&requests[1].body).unwrap();
+    assert!(
+        discord["content"]
+            .as_str()
+            .unwrap()
+            .contains("\"Proposal Mitra\" was graded")
+    );
+
+    // Nothing changed since, so nothing is posted.
+    let report = watcher.poll_once().await.unwrap();
+    assert!(report.events.is_empty());
+    assert_eq!(hooks.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn long_discord_messages_say_how_many_changes_were_left_out() {
+    let mock = MockSpot::start().await;
+    let hooks = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&hooks)
+        .await;
+    mock.set_answered(true);
+    mock.set_graded(true);
+
+    let mut watcher = Watcher::builder(logged_in_client(&mock).await)
+        .webhook(&format!("{}/hook", hooks.uri()), WebhookFormat::Discord)
+        .unwrap()
+        .build()
+        .unwrap();
+    watcher.poll_once().await.unwrap();
+
+    // A note long enough to leave no room for the next change.
+    let notes = "Sudah baik. ".repeat(160);
+    let page = fixture("topic.html").replace(
+        "<!-- ANSWER -->",
+        &fixture("topic_answer.html").replace(
+            "<!-- GRADE -->",
+            &fixture("topic_grade.html").replace("Proposal sudah baik.", &notes),
+        ),
+    );
+    Mock::given(method("GET"))
+        .and(path_regex(r"^/mhs/topik/"))
+        .respond_with(html_with_status(200, page))
+        .with_priority(1)
+        .mount(&mock.spot)
+        .await;
+
+    let report = watcher.poll_once().await.unwrap();
+    assert_eq!(report.events.len(), 2);
+    assert_eq!(report.delivered, 1);
+
+    let requests = hooks.received_requests().await.unwrap();
+    let discord: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    let content = discord["content"].as_str().unwrap();
+    assert!(content.chars().count() <= 2000);
+    assert!(content.starts_with("• New lecturer notes on \"Proposal Mitra\""));
+    assert!(content.ends_with("\n…and 1 more change"), "{}", content);
+}
+
+#[tokio::test]
+async fn failed_deliveries_are_retried_then_dead_lettered() {
+    let mock = MockSpot::start().await;
+    let hooks = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&hooks)
+        .await;
+
+    let dead_letter = temp_path("dead-letter.jsonl");
+    let mut watcher = Watcher::builder(logged_in_client(&mock).await)
+        .webhook(&hooks.uri(), WebhookFormat::Slack)
+        .unwrap()
+        .max_attempts(3)
+        .retry_delay(Duration::from_millis(10))
+        .dead_letter_file(&dead_letter)
+        .build()
+        .unwrap();
+
+    watcher.poll_once().await.unwrap();
+    mock.set_answered(true);
+    mock.set_graded(true);
+    let report = watcher.poll_once().await.unwrap();
+    assert_eq!(report.delivered, 0);
+    assert_eq!(report.failed, 1);
+    assert_eq!(hooks.received_requests().await.unwrap().len(), 3);
+
+    let letters = std::fs::read_to_string(&dead_letter).unwrap();
+    let letter: Value = serde_json::from_str(letters.lines().next().unwrap()).unwrap();
+    assert!(letter["error"].as_str().unwrap().contains("503"));
+    assert!(
+        letter["payload"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("graded")
+    );
+    std::fs::remove_file(&dead_letter).unwrap();
+}
+
+#[tokio::test]
+async fn client_errors_are_not_retried() {
+    let mock = MockSpot::start().await;
+    let hooks = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&hooks)
+        .await;
+
+    let mut watcher = Watcher::builder(logged_in_client(&mock).await)
+        .webhook(&hooks.uri(), WebhookFormat::Json)
+        .unwrap()
+        .retry_delay(Duration::from_millis(10))
+        .build()
+        .unwrap();
+
+    watcher.poll_once().await.unwrap();
+    mock.set_answered(true);
+    let report = watcher.poll_once().await.unwrap();
+    // Submitting an answer is not a change event on its own.
+    assert!(report.events.is_empty());
+
+    mock.set_graded(true);
+    let report = watcher.poll_once().await.unwrap();
+    assert_eq!(report.failed, 1);
+    assert_eq!(hooks.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn state_file_keeps_the_baseline_across_restarts() {
+    let mock = MockSpot::start().await;
+    let state = temp_path("watch-state.json");
+
+    let mut first = Watcher::builder(logged_in_client(&mock).await)
+        .state_file(&state)
+        .build()
+        .unwrap();
+    first.poll_once().await.unwrap();
+    drop(first);
+
+    mock.set_answered(true);
+    mock.set_graded(true);
+    let mut restarted = Watcher::builder(logged_in_client(&mock).await)
+        .state_file(&state)
+        .build()
+        .unwrap();
+    let report = restarted.poll_once().await.unwrap();
+    assert_eq!(report.events.len(), 2);
+    std::fs::remove_file(&state).unwrap();
+}
+
+#[tokio::test]
+async fn a_damaged_state_file_is_reported_once_and_replaced() {
+    let mock = MockSpot::start().await;
+    let state = temp_path("damaged-state.json");
+    std::fs::write(&state, "{\"taken_at\": \"2025-").unwrap();
+
+    let mut watcher = Watcher::builder(logged_in_client(&mock).await)
+        .state_file(&state)
+        .build()
+        .unwrap();
+    let report = watcher.poll_once().await.unwrap();
+    assert!(report.discarded_state.is_some());
+    assert!(report.events.is_empty());
+    let saved: Value = serde_json::from_str(&std::fs::read_to_string(&state).unwrap()).unwrap();
+    assert!(saved.get("taken_at").is_some());
+
+    let report = watcher.poll_once().await.unwrap();
+    assert!(report.discarded_state.is_none());
+    std::fs::remove_file(&state).unwrap();
+}
+
+#[tokio::test]
+async fn an_unwritable_dead_letter_file_does_not_keep_the_old_baseline() {
+    let mock = MockSpot::start().await;
+    let hooks = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&hooks)
+        .await;
+
+    let mut watcher = Watcher::builder(logged_in_client(&mock).await)
+        .webhook(&hooks.uri(), WebhookFormat::Json)
+        .unwrap()
+        .dead_letter_file(temp_path("missing-dir").join("dead-letter.jsonl"))
+        .build()
+        .unwrap();
+    watcher.poll_once().await.unwrap();
+
+    mock.set_answered(true);
+    mock.set_graded(true);
+    let report = watcher.poll_once().await.unwrap();
+    assert_eq!(report.failed, 1);
+    assert_eq!(report.dead_letter_errors.len(), 1);
+
+    // The changes were handled, so they are not delivered again.
+    let report = watcher.poll_once().await.unwrap();
+    assert!(report.events.is_empty());
+    assert_eq!(hooks.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn retry_after_is_capped_by_the_max_retry_delay() {
+    let mock = MockSpot::start().await;
+    let hooks = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "86400"))
+        .mount(&hooks)
+        .await;
+
+    let mut watcher = Watcher::builder(logged_in_client(&mock).await)
+        .webhook(&hooks.uri(), WebhookFormat::Json)
+        .unwrap()
+        .max_attempts(2)
+        .max_retry_delay(Duration::from_millis(20))
+        .build()
+        .unwrap();
+    watcher.poll_once().await.unwrap();
+
+    mock.set_answered(true);
+    mock.set_graded(true);
+    let report = tokio::time::timeout(Duration::from_secs(10), watcher.poll_once())
+        .await
+        .expect("the watcher waited for the full Retry-After")
+        .unwrap();
+    assert_eq!(report.failed, 1);
+    assert_eq!(hooks.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn run_until_stops_on_shutdown() {
+    let mock = MockSpot::start().await;
+    let mut watcher = Watcher::builder(logged_in_client(&mock).await)
+        .interval(Duration::from_millis(20))
+        .jitter(Duration::from_millis(5))
+        .build()
+        .unwrap();
+
+    let mut errors = 0;
+    watcher
+        .run_until(tokio::time::sleep(Duration::from_millis(100)), |_| {
+            errors += 1
+        })
+        .await;
+    assert_eq!(errors, 0);
+}